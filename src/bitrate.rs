@@ -0,0 +1,232 @@
+use libwebrtc::ffi::stats_collector::Rs_VideoSenderStats;
+use log::debug;
+
+/// Bounds and starting point for a `BitrateController`'s estimate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BitrateConfig {
+    pub(crate) min_bps: u32,
+    pub(crate) max_bps: u32,
+    pub(crate) initial_bps: u32,
+}
+
+impl Default for BitrateConfig {
+    fn default() -> Self {
+        Self {
+            min_bps: 150_000,
+            max_bps: 4_000_000,
+            initial_bps: 1_000_000,
+        }
+    }
+}
+
+/// Loss fraction below which the estimate is grown (when not delay-limited).
+const LOW_LOSS_THRESHOLD: f64 = 0.02;
+/// Loss fraction above which the estimate is multiplicatively cut.
+const HIGH_LOSS_THRESHOLD: f64 = 0.10;
+/// Multiplicative increase applied while far below `max_bps`.
+const MULTIPLICATIVE_INCREASE: f64 = 1.08;
+/// Additive increase (bps) applied once near `max_bps`.
+const ADDITIVE_INCREASE_BPS: u32 = 20_000;
+/// An estimate is considered "far below" the max once it sits below this
+/// fraction of `max_bps`, and switches from multiplicative to additive
+/// growth above it.
+const MULTIPLICATIVE_REGION: f64 = 0.5;
+
+/// Additive-increase/multiplicative-decrease estimate of the bitrate a
+/// `PeerConnection`'s link can sustain, driven by periodic samples of
+/// `PeerConnection::get_stats()`. Mirrors the scheme browsers use for
+/// sender-side congestion control: grow gently while loss is low, hold
+/// steady while it is moderate, back off hard once it is high, and never
+/// leave the configured `[min_bps, max_bps]` range.
+#[derive(Debug, Clone)]
+pub(crate) struct BitrateController {
+    config: BitrateConfig,
+    estimate_bps: u32,
+}
+
+impl BitrateController {
+    pub(crate) fn new(config: BitrateConfig) -> Self {
+        let estimate_bps = config.initial_bps.clamp(config.min_bps, config.max_bps);
+
+        Self { config, estimate_bps }
+    }
+
+    pub(crate) fn estimate_bps(&self) -> u32 {
+        self.estimate_bps
+    }
+
+    /// Folds in one interval's observed `loss_fraction` (0.0-1.0) and
+    /// whether the link is currently delay-limited (e.g. growing RTT),
+    /// returning the updated estimate.
+    pub(crate) fn observe(&mut self, loss_fraction: f64, delay_limited: bool) -> u32 {
+        let estimate = self.estimate_bps as f64;
+
+        let updated = if loss_fraction > HIGH_LOSS_THRESHOLD {
+            estimate * (1.0 - 0.5 * loss_fraction)
+        } else if loss_fraction < LOW_LOSS_THRESHOLD && !delay_limited {
+            if estimate < self.config.max_bps as f64 * MULTIPLICATIVE_REGION {
+                estimate * MULTIPLICATIVE_INCREASE
+            } else {
+                estimate + ADDITIVE_INCREASE_BPS as f64
+            }
+        } else {
+            estimate
+        };
+
+        self.estimate_bps = (updated.round() as u32).clamp(self.config.min_bps, self.config.max_bps);
+
+        debug!(
+            "bitrate estimate now {} bps (loss={:.3}, delay_limited={})",
+            self.estimate_bps, loss_fraction, delay_limited
+        );
+
+        self.estimate_bps
+    }
+}
+
+/// Aggregate loss fraction (0.0-1.0) across a peer connection's video
+/// senders, for feeding into `BitrateController::observe`. Sums lost and
+/// sent packets across every sender rather than averaging per-sender
+/// fractions, so one struggling sender among several can't be masked by
+/// the others.
+pub(crate) fn loss_fraction_from_stats(stats: &[Rs_VideoSenderStats]) -> f64 {
+    let (lost, sent) = stats.iter().fold((0i64, 0i64), |(lost, sent), s| {
+        (lost + s.packets_lost as i64, sent + s.packets_sent as i64)
+    });
+
+    if sent <= 0 {
+        return 0.0;
+    }
+
+    (lost.max(0) as f64 / sent as f64).min(1.0)
+}
+
+/// Periodically samples every peer connection held by `state` and steers
+/// the outgoing video bitrate with AIMD, so WHIP/WHEP load sessions emulate
+/// realistic sender behavior instead of blasting a fixed rate.
+///
+/// Every resource's `PeerConnection` shares a clone of the same
+/// `RustTrackVideoSource` (one GStreamer pipeline feeding every connection;
+/// see `WishState::set_target_bitrate`), so there's no per-connection
+/// encoder to retarget independently. Each resource still gets its own
+/// `BitrateController` tracking its own observed loss, but the bitrate
+/// actually applied each tick is the minimum estimate across all of them --
+/// the shared encoder can only run at a rate every connection can sustain,
+/// so the worst-off connection sets the ceiling for everyone.
+pub(crate) fn spawn_bitrate_sampler(
+    state: crate::wish::WishState,
+    config: BitrateConfig,
+    interval: std::time::Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let controllers: dashmap::DashMap<String, BitrateController> = dashmap::DashMap::new();
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            let mut shared_estimate_bps = None;
+
+            for resource in state.resources().iter() {
+                let loss_fraction = resource.value().loss_fraction();
+                let mut controller = controllers
+                    .entry(resource.key().clone())
+                    .or_insert_with(|| BitrateController::new(config));
+
+                let estimate_bps = controller.observe(loss_fraction, false);
+                shared_estimate_bps = Some(shared_estimate_bps.map_or(estimate_bps, |min: u32| min.min(estimate_bps)));
+            }
+
+            if let Some(estimate_bps) = shared_estimate_bps {
+                state.set_target_bitrate(estimate_bps);
+            }
+
+            controllers.retain(|resource_id, _| state.resources().contains_key(resource_id));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_grows_multiplicatively_while_far_below_the_max() {
+        let mut controller = BitrateController::new(BitrateConfig {
+            min_bps: 100_000,
+            max_bps: 4_000_000,
+            initial_bps: 1_000_000,
+        });
+
+        let estimate = controller.observe(0.0, false);
+
+        assert_eq!(1_080_000, estimate);
+    }
+
+    #[test]
+    fn it_grows_additively_once_near_the_max() {
+        let mut controller = BitrateController::new(BitrateConfig {
+            min_bps: 100_000,
+            max_bps: 2_000_000,
+            initial_bps: 1_500_000,
+        });
+
+        let estimate = controller.observe(0.0, false);
+
+        assert_eq!(1_520_000, estimate);
+    }
+
+    #[test]
+    fn it_backs_off_multiplicatively_on_heavy_loss() {
+        let mut controller = BitrateController::new(BitrateConfig {
+            min_bps: 100_000,
+            max_bps: 4_000_000,
+            initial_bps: 1_000_000,
+        });
+
+        let estimate = controller.observe(0.2, false);
+
+        assert_eq!(900_000, estimate);
+    }
+
+    #[test]
+    fn it_never_drops_below_the_configured_minimum() {
+        let mut controller = BitrateController::new(BitrateConfig {
+            min_bps: 500_000,
+            max_bps: 4_000_000,
+            initial_bps: 600_000,
+        });
+
+        let estimate = controller.observe(1.0, false);
+
+        assert_eq!(500_000, estimate);
+    }
+
+    #[test]
+    fn it_sums_loss_across_senders_rather_than_averaging() {
+        let stats = vec![
+            Rs_VideoSenderStats {
+                packets_sent: 100,
+                packets_lost: 10,
+                ..Default::default()
+            },
+            Rs_VideoSenderStats {
+                packets_sent: 100,
+                packets_lost: 0,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(0.05, loss_fraction_from_stats(&stats));
+    }
+
+    #[test]
+    fn it_holds_steady_between_the_loss_thresholds() {
+        let mut controller = BitrateController::new(BitrateConfig::default());
+        let initial = controller.estimate_bps();
+
+        let estimate = controller.observe(0.05, false);
+
+        assert_eq!(initial, estimate);
+    }
+}
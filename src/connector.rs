@@ -0,0 +1,130 @@
+use crate::error::{Result, ServerError};
+use log::{error, info};
+use serde_json::Value;
+use sqlx::sqlite::SqlitePool;
+use std::time::SystemTime;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::task::JoinHandle;
+
+const EVENT_QUEUE_SIZE: usize = 1_000;
+
+/// A structured event pushed by a `Session` at a state transition or stats
+/// snapshot, destined for the `event` table.
+#[derive(Debug, Clone)]
+pub(crate) struct Event {
+    pub(crate) session_id: String,
+    pub(crate) peer_connection_id: Option<String>,
+    pub(crate) kind: EventKind,
+    pub(crate) timestamp: SystemTime,
+    pub(crate) elapsed: Option<u64>,
+    pub(crate) data: Value,
+}
+
+#[derive(Debug, Clone, strum::ToString)]
+pub(crate) enum EventKind {
+    SessionCreated,
+    SessionStarted,
+    SessionStopped,
+    SessionReaped,
+    PeerConnectionAdded,
+    StatsSnapshot,
+}
+
+/// A handle `Session`s hold to push events onto the connector's bounded
+/// queue; cheap to clone, backed by a single background writer task.
+#[derive(Clone)]
+pub(crate) struct Connector {
+    sender: Sender<Event>,
+}
+
+impl Connector {
+    /// Connects to `database_url`, runs migrations for the `event` table,
+    /// and spawns the background task that drains the queue into it.
+    pub(crate) async fn connect(database_url: &str) -> Result<(Self, JoinHandle<()>)> {
+        let pool = SqlitePool::connect(database_url)
+            .await
+            .map_err(|e| ServerError::ConnectorError(e.to_string()))?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS event (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                peer_connection_id TEXT,
+                kind TEXT NOT NULL,
+                timestamp_ms INTEGER NOT NULL,
+                elapsed INTEGER,
+                data TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .map_err(|e| ServerError::ConnectorError(e.to_string()))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_event_session_time ON event (session_id, timestamp_ms)")
+            .execute(&pool)
+            .await
+            .map_err(|e| ServerError::ConnectorError(e.to_string()))?;
+
+        let (sender, receiver) = channel(EVENT_QUEUE_SIZE);
+        let join_handle = tokio::spawn(Self::run(pool, receiver));
+
+        Ok((Self { sender }, join_handle))
+    }
+
+    async fn run(pool: SqlitePool, mut receiver: Receiver<Event>) {
+        while let Some(event) = receiver.recv().await {
+            let timestamp_ms = event
+                .timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or_default();
+
+            let result = sqlx::query(
+                "INSERT INTO event (session_id, peer_connection_id, kind, timestamp_ms, elapsed, data)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&event.session_id)
+            .bind(&event.peer_connection_id)
+            .bind(event.kind.to_string())
+            .bind(timestamp_ms)
+            .bind(event.elapsed.map(|e| e as i64))
+            .bind(event.data.to_string())
+            .execute(&pool)
+            .await;
+
+            if let Err(err) = result {
+                error!("Failed to persist event: {}", err);
+            }
+        }
+
+        info!("Connector event queue closed, shutting down writer task");
+    }
+
+    /// Pushes `event` onto the queue; drops it and logs rather than
+    /// blocking the caller if the writer is falling behind.
+    pub(crate) fn push(&self, event: Event) {
+        if let Err(err) = self.sender.try_send(event) {
+            error!("Dropping event, connector queue full or closed: {}", err);
+        }
+    }
+}
+
+/// Periodically calls `Session::peer_connection_stats` for every session in
+/// `data`, so stats snapshots keep flowing to the connector even when no
+/// client is actively polling `get_stats`.
+pub(crate) fn spawn_stats_sampler(
+    data: std::sync::Arc<crate::data::Data>,
+    interval: std::time::Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            for session in data.sessions.iter() {
+                session.value().peer_connection_stats().await;
+            }
+        }
+    })
+}
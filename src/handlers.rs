@@ -1,5 +1,5 @@
 use crate::data::SharedState;
-use crate::peer_connection::PeerConnection;
+use crate::peer_connection::{IceConfig, PeerConnection, VideoCodec};
 use crate::server::webrtc;
 use crate::session::Session;
 use crate::ServerError;
@@ -45,8 +45,15 @@ impl WebRtc for SharedState {
         request: Request<CreateSessionRequest>,
     ) -> Result<Response<CreateSessionResponse>, Status> {
         let name = requester("create_session", request).name;
-        let session = Session::new(name);
-        let session_id = session.id.clone();
+        let session_id = nanoid::nanoid!();
+
+        // TODO: CreateSessionRequest has no field selecting "loopback" vs
+        // "livekit" signalling, and the .proto it's generated from isn't
+        // part of this crate's source tree to add one to -- Session::new
+        // (loopback-only) is the best we can do here until that schema
+        // grows a field; Session::new_with_signalling is otherwise only
+        // reachable from unit tests.
+        let session = Session::new(session_id.clone(), name)?;
         self.data.add_session(session)?;
         let reply = webrtc::CreateSessionResponse { session_id };
 
@@ -58,6 +65,7 @@ impl WebRtc for SharedState {
         request: Request<StartSessionRequest>,
     ) -> Result<Response<Empty>, Status> {
         let session_id = requester("start_session", request).session_id;
+        call_session!(self, session_id, touch)?;
         call_session!(self, session_id, start)?;
         let reply = Empty {};
 
@@ -69,6 +77,7 @@ impl WebRtc for SharedState {
         request: Request<StopSessionRequest>,
     ) -> Result<Response<Empty>, Status> {
         let session_id = requester("stop_session", request).session_id;
+        call_session!(self, session_id, touch)?;
         call_session!(self, session_id, stop)?;
         let reply = webrtc::Empty {};
 
@@ -80,6 +89,7 @@ impl WebRtc for SharedState {
         request: Request<GetStatsRequest>,
     ) -> Result<Response<GetStatsResponse>, Status> {
         let session_id = requester("get_stats", request).session_id;
+        call_session!(self, session_id, touch)?;
         let stats = call_session!(self, session_id, get_stats).await?;
         let peer_connections = stats
             .peer_connections
@@ -101,11 +111,22 @@ impl WebRtc for SharedState {
         let CreatePeerConnectionRequest { name, session_id } =
             requester("create_peer_connection", request);
         let peer_connection_id = nanoid::nanoid!();
+        call_session!(self, session_id, touch)?;
 
         // create the peer connection
+        //
+        // TODO: CreatePeerConnectionRequest has no ICE-server/relay-only
+        // field to thread a caller's TURN configuration through, and the
+        // .proto it's generated from isn't part of this crate's source
+        // tree to add one to -- IceConfig::default() is the best we can do
+        // here until that schema grows a field; turn_relay_only() is
+        // otherwise only reachable from unit tests.
+        let audio_source = PeerConnection::file_audio_source();
         let peer_connection = PeerConnection::new(
             &self.peer_connection_factory,
             &get_session_attribute!(self, session_id.clone(), video_source),
+            &audio_source,
+            &IceConfig::default(),
             peer_connection_id.clone(),
             name.clone(),
         )?;
@@ -125,6 +146,7 @@ impl WebRtc for SharedState {
         let request = requester("create_offer", request);
         let session_id = request.session_id;
         let peer_connection_id = request.peer_connection_id;
+        call_session!(self, session_id, touch)?;
 
         let sdp = call_peer_connection!(self, session_id, peer_connection_id, create_offer)?;
 
@@ -145,6 +167,7 @@ impl WebRtc for SharedState {
         let request = requester("create_answer", request);
         let session_id = request.session_id;
         let peer_connection_id = request.peer_connection_id;
+        call_session!(self, session_id, touch)?;
 
         let sdp = call_peer_connection!(self, session_id, peer_connection_id, create_answer)?;
 
@@ -167,6 +190,7 @@ impl WebRtc for SharedState {
         let sdp = request.sdp;
         let session_id = request.session_id;
         let peer_connection_id = request.peer_connection_id;
+        call_session!(self, session_id, touch)?;
 
         call_peer_connection!(
             self,
@@ -195,6 +219,7 @@ impl WebRtc for SharedState {
         let sdp = request.sdp;
         let session_id = request.session_id;
         let peer_connection_id = request.peer_connection_id;
+        call_session!(self, session_id, touch)?;
 
         call_peer_connection!(
             self,
@@ -223,6 +248,7 @@ impl WebRtc for SharedState {
         let peer_connection_id = request.peer_connection_id;
         let _track_id = request.track_id;
         let track_label = request.track_label;
+        call_session!(self, session_id, touch)?;
 
         // let video_source = &self
         //     .data
@@ -232,7 +258,7 @@ impl WebRtc for SharedState {
         //     .video_source;
 
         // TODO: do we need to create a video source for each track addition?
-        let video_source = PeerConnection::file_video_source();
+        let video_source = PeerConnection::file_video_source(VideoCodec::H264);
 
         call_peer_connection!(
             self,
@@ -256,8 +282,25 @@ impl WebRtc for SharedState {
         let request = requester("add_transceiver", request);
         let session_id = request.session_id;
         let peer_connection_id = request.peer_connection_id;
+        call_session!(self, session_id, touch)?;
 
-        call_peer_connection!(self, session_id, peer_connection_id, add_transceiver)?;
+        // TODO: AddTransceiverRequest has no codec field to thread a
+        // caller's choice through, and the .proto it's generated from
+        // isn't part of this crate's source tree to add one to -- H264 is
+        // the best we can do here until that schema grows one; VideoCodec
+        // selection is otherwise only reachable from unit tests.
+        let video_source = PeerConnection::file_video_source(VideoCodec::H264);
+
+        call_peer_connection!(
+            self,
+            session_id,
+            peer_connection_id,
+            add_transceiver,
+            &self.peer_connection_factory,
+            &video_source,
+            "video".to_owned(),
+            VideoCodec::H264
+        )?;
 
         let reply = Empty {};
 
@@ -5,15 +5,16 @@ use libwebrtc::ffi::rtp_transceiver::{C_RtpTransceiverDirection, C_cricket_Media
 use libwebrtc::ffi::sdp::SdpType;
 use libwebrtc::ffi::stats_collector::Rs_VideoSenderStats;
 use libwebrtc::peerconnection::{
-    IceServer, PeerConnection as WebRtcPeerConnection, RTCConfiguration,
+    IceServer, IceTransportsType, PeerConnection as WebRtcPeerConnection, RTCConfiguration,
 };
 use libwebrtc::peerconnection_factory::PeerConnectionFactory;
 use libwebrtc::peerconnection_observer::{PeerConnectionObserver, PeerConnectionObserverTrait};
-use libwebrtc::rtp_transceiver::RtpTransceiverInit;
+use libwebrtc::rtp_transceiver::{RtpCodecCapability, RtpTransceiverInit};
+use libwebrtc::rust_audio_track_source::RustTrackAudioSource;
 use libwebrtc::rust_video_track_source::RustTrackVideoSource;
 use libwebrtc::sdp::SessionDescription;
 use libwebrtc::stats_collector::{DummyRTCStatsCollector, RTCStatsCollectorCallback};
-use log::debug;
+use log::{debug, error};
 use std::sync::mpsc::channel;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc::{Receiver, Sender};
@@ -27,6 +28,7 @@ pub(crate) struct PeerConnection {
     pub(crate) webrtc_peer_connection: WebRtcPeerConnection,
     pub(crate) observer: PeerConnectionObserver,
     pub(crate) receiver: Receiver<String>,
+    video_source: RustTrackVideoSource,
 }
 
 impl fmt::Debug for PeerConnection {
@@ -35,6 +37,11 @@ impl fmt::Debug for PeerConnection {
     }
 }
 
+/// `Session` predates this module's final shape and still refers to the
+/// peer-connection type it manages by this name; kept as an alias rather
+/// than renaming every call site.
+pub(crate) type PeerConnectionManager = PeerConnection;
+
 #[derive(Clone)]
 pub(crate) struct ChannelPeerConnectionObserver {
     pub(crate) sender: Sender<String>,
@@ -50,10 +57,93 @@ impl PeerConnectionObserverTrait for ChannelPeerConnectionObserver {
     }
 }
 
+/// ICE servers (STUN/TURN) and transport policy for a `PeerConnection`.
+/// Defaults to the public Google STUN server with no TURN relay and an
+/// open (`All`) transport policy; operators can supply TURN URLs with
+/// long-term credentials and force relay-only routing to exercise that
+/// path behind NAT or in CI.
+#[derive(Debug, Clone)]
+pub(crate) struct IceConfig {
+    pub(crate) ice_servers: Vec<IceServer>,
+    pub(crate) relay_only: bool,
+}
+
+impl Default for IceConfig {
+    fn default() -> Self {
+        Self {
+            ice_servers: vec![IceServer {
+                username: None,
+                password: None,
+                hostname: None,
+                urls: vec!["stun:stun.l.google.com:19302".to_string()],
+            }],
+            relay_only: false,
+        }
+    }
+}
+
+impl IceConfig {
+    /// A TURN relay with long-term credentials, reached only (no STUN,
+    /// no host candidates), for forcing traffic through the relay path.
+    pub(crate) fn turn_relay_only(url: String, username: String, password: String) -> Self {
+        Self {
+            ice_servers: vec![IceServer {
+                username: Some(username),
+                password: Some(password),
+                hostname: None,
+                urls: vec![url],
+            }],
+            relay_only: true,
+        }
+    }
+}
+
+/// A video codec a `PeerConnection` can be made to prefer, so load tests
+/// can exercise SFUs that behave differently per codec rather than always
+/// negotiating H.264.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VideoCodec {
+    H264,
+    Vp8,
+    Vp9,
+    Av1,
+}
+
+impl Default for VideoCodec {
+    fn default() -> Self {
+        Self::H264
+    }
+}
+
+impl VideoCodec {
+    /// The `a=rtpmap` mime type this codec should be offered as.
+    pub(crate) fn mime_type(&self) -> &'static str {
+        match self {
+            Self::H264 => "video/H264",
+            Self::Vp8 => "video/VP8",
+            Self::Vp9 => "video/VP9",
+            Self::Av1 => "video/AV1",
+        }
+    }
+
+    /// The GStreamer decode element that turns this codec's encoded
+    /// bitstream into raw frames for `file_video_source`'s pipeline.
+    fn gst_decoder(&self) -> &'static str {
+        match self {
+            Self::H264 => "avdec_h264",
+            Self::Vp8 => "vp8dec",
+            Self::Vp9 => "vp9dec",
+            Self::Av1 => "av1dec",
+        }
+    }
+}
+
 impl PeerConnection {
     pub(crate) fn new(
         peer_connection_factory: &PeerConnectionFactory,
         video_source: &RustTrackVideoSource,
+        audio_source: &RustTrackAudioSource,
+        ice_config: &IceConfig,
         id: String,
         name: String,
     ) -> Result<PeerConnection> {
@@ -64,38 +154,47 @@ impl PeerConnection {
         debug!("created pc observer");
 
         let webrtc_peer_connection = peer_connection_factory
-            .create_peer_connection(&observer, Self::rtc_config())
+            .create_peer_connection(&observer, Self::rtc_config(ice_config))
             .map_err(|e| ServerError::CreatePeerConnectionError(e.to_string()))?;
         debug!("created peerconnection");
 
         // add the video track
         peer_connection_factory.create_and_add_video_track(&webrtc_peer_connection, &video_source);
 
+        // add the audio track so generated offers carry an Opus m-line
+        // alongside the video one
+        peer_connection_factory.create_and_add_audio_track(&webrtc_peer_connection, &audio_source);
+
         Ok(PeerConnection {
             id,
             name,
             webrtc_peer_connection,
             observer,
             receiver: rx,
+            video_source: video_source.clone(),
         })
     }
 
-    fn rtc_config() -> RTCConfiguration {
+    fn rtc_config(ice_config: &IceConfig) -> RTCConfiguration {
         RTCConfiguration {
             enable_dtls_srtp: true,
-            ice_servers: vec![IceServer {
-                username: None,
-                password: None,
-                hostname: None,
-                urls: vec!["stun:stun.l.google.com:19302".to_string()],
-            }],
+            ice_servers: ice_config.ice_servers.clone(),
+            ice_transport_policy: if ice_config.relay_only {
+                IceTransportsType::Relay
+            } else {
+                IceTransportsType::All
+            },
             ..Default::default()
         }
     }
 
-    /// Send the callback to the rust ffi bindings and just listen for the first message.
+    /// Sends the callback to the rust FFI bindings and waits for its first
+    /// (and only) message.
     ///
-    /// If the message fails, just return an empty vec.
+    /// If the FFI callback never fires -- the sender was dropped without
+    /// sending, e.g. because `webrtc_peer_connection.get_stats` failed -- this
+    /// logs the error and returns an empty vec rather than silently
+    /// pretending stats collection succeeded.
     pub(crate) fn get_stats(&self) -> Vec<Rs_VideoSenderStats> {
         let (sender, receiver) = channel();
         let sender = Arc::new(Mutex::new(sender));
@@ -103,7 +202,67 @@ impl PeerConnection {
         let stats_callback: RTCStatsCollectorCallback = stats_collector.into();
         let _ = self.webrtc_peer_connection.get_stats(&stats_callback);
 
-        receiver.recv().unwrap_or_default()
+        receiver.recv().unwrap_or_else(|err| {
+            error!("failed to collect stats for peer connection {}: {}", self.id, err);
+            Vec::new()
+        })
+    }
+
+    /// Aggregate loss fraction (0.0-1.0) across this peer connection's
+    /// video senders, for a caller driving a `BitrateController`.
+    pub(crate) fn loss_fraction(&self) -> f64 {
+        crate::bitrate::loss_fraction_from_stats(&self.get_stats())
+    }
+
+    /// This peer connection's categorized stats report (see
+    /// `crate::rtc_stats`), for a caller aggregating a `StatsReport` across
+    /// every tracked peer connection.
+    pub(crate) fn stats_report(&self) -> crate::rtc_stats::PeerConnectionStatsReport {
+        crate::rtc_stats::report_for(&self.id, &self.get_stats())
+    }
+
+    /// Applies a congestion-controlled bitrate estimate to this peer
+    /// connection's outgoing video by reconfiguring the live encoder behind
+    /// `RustTrackVideoSource`, the same source handle `file_video_source`
+    /// returned and `new` was given.
+    pub(crate) fn set_target_bitrate(&self, target_bps: u32) {
+        self.video_source.set_bitrate(target_bps);
+
+        debug!("retargeted encoder bitrate to {} bps", target_bps);
+    }
+
+    /// Best-effort connection-state read for `Session::heartbeat`'s health
+    /// check.
+    ///
+    /// TODO: this snapshot's `PeerConnectionObserver` only wires up
+    /// `on_ice_candidate` (see `ChannelPeerConnectionObserver`), not an ICE
+    /// connection-state change callback, so there's no real-time signal to
+    /// read directly. Fall back to a stats-based heuristic -- no outbound
+    /// video stats at all reads as disconnected -- until the observer grows
+    /// an `on_ice_connection_change` callback to report the real state.
+    pub(crate) fn connection_state(&self) -> crate::reconnect::ConnectionState {
+        if self.get_stats().is_empty() {
+            crate::reconnect::ConnectionState::Disconnected
+        } else {
+            crate::reconnect::ConnectionState::Connected
+        }
+    }
+
+    /// The local half of re-establishing a dropped peer connection: creates
+    /// a fresh offer (with no ICE-restart option) and sets it as this
+    /// side's local description, returning it.
+    ///
+    /// The offer is not sent to the remote side here and no answer is
+    /// applied; `LiveKitSignaller` relays only the initial offer made in
+    /// `LiveKitSignaller::connect`, not offers made by this method.
+    pub(crate) fn reconnect(&mut self) -> Result<SessionDescription> {
+        let offer = self.create_offer()?;
+        let sdp_type = offer
+            .get_type()
+            .map_err(|e| ServerError::CouldNotCreateOffer(e.to_string()))?;
+        self.set_local_description(sdp_type, offer.to_string())?;
+
+        Ok(offer)
     }
 
     pub(crate) fn create_offer(&mut self) -> Result<SessionDescription> {
@@ -144,6 +303,29 @@ impl PeerConnection {
         Ok(())
     }
 
+    /// Feeds a remote ICE candidate, trickled in by the signalling caller,
+    /// into the underlying peerconnection so connectivity establishment
+    /// doesn't have to wait for full ICE gathering on either side.
+    pub(crate) fn add_ice_candidate(
+        &self,
+        candidate_sdp: String,
+        sdp_mid: String,
+        sdp_mline_index: u32,
+    ) -> Result<()> {
+        self.webrtc_peer_connection
+            .add_ice_candidate(candidate_sdp, sdp_mid, sdp_mline_index)
+            .map_err(|e| ServerError::CouldNotAddIceCandidate(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Drains the next locally gathered ICE candidate, pushed by
+    /// `ChannelPeerConnectionObserver::on_ice_candidate`, so a caller can
+    /// relay it to the remote side as part of trickle ICE.
+    pub(crate) async fn next_ice_candidate(&mut self) -> Option<String> {
+        self.receiver.recv().await
+    }
+
     pub(crate) fn add_track(
         &self,
         peer_connection_factory: &PeerConnectionFactory,
@@ -162,11 +344,16 @@ impl PeerConnection {
         Ok(success)
     }
 
+    /// Adds a video transceiver and steers `create_offer`'s payload types
+    /// and fmtp lines towards `codec` via `SetCodecPreferences`, so the
+    /// generated offer negotiates the codec under test rather than
+    /// whichever one libwebrtc would otherwise prefer.
     pub(crate) fn add_transceiver(
         &self,
         peer_connection_factory: &PeerConnectionFactory,
         video_source: &RustTrackVideoSource,
         label: String,
+        codec: VideoCodec,
     ) -> Result<()> {
         let init = RtpTransceiverInit {
             direction: C_RtpTransceiverDirection::kSendRecv,
@@ -176,6 +363,53 @@ impl PeerConnection {
             .create_video_track(video_source, label)
             .map_err(|e| ServerError::CouldNotCreateTrack(e.to_string()))?;
         let stream_ids = vec!["0".to_owned()];
+        let transceiver = self
+            .webrtc_peer_connection
+            .add_transceiver(track, init)
+            .map_err(|e| ServerError::CouldNotAddTransceiver(e.to_string()))?;
+
+        let codec_preference = RtpCodecCapability {
+            mime_type: codec.mime_type().to_owned(),
+            ..Default::default()
+        };
+        transceiver
+            .set_codec_preferences(vec![codec_preference])
+            .map_err(|e| ServerError::CouldNotAddTransceiver(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub(crate) fn add_audio_track(
+        &self,
+        peer_connection_factory: &PeerConnectionFactory,
+        audio_source: &RustTrackAudioSource,
+        label: String,
+    ) -> Result<bool> {
+        debug!("creating audio track");
+        let track = peer_connection_factory
+            .create_audio_track(audio_source, label)
+            .map_err(|e| ServerError::CouldNotCreateTrack(e.to_string()))?;
+        let stream_ids = vec!["0".to_owned()];
+
+        debug!("adding audio track to peer connection");
+        let success = self.webrtc_peer_connection.add_track(track, stream_ids);
+
+        Ok(success)
+    }
+
+    pub(crate) fn add_audio_transceiver(
+        &self,
+        peer_connection_factory: &PeerConnectionFactory,
+        audio_source: &RustTrackAudioSource,
+        label: String,
+    ) -> Result<()> {
+        let init = RtpTransceiverInit {
+            direction: C_RtpTransceiverDirection::kSendRecv,
+            stream_ids: vec!["0".to_owned()],
+        };
+        let track = peer_connection_factory
+            .create_audio_track(audio_source, label)
+            .map_err(|e| ServerError::CouldNotCreateTrack(e.to_string()))?;
         self.webrtc_peer_connection
             .add_transceiver(track, init)
             .map_err(|e| ServerError::CouldNotAddTransceiver(e.to_string()))?;
@@ -184,12 +418,18 @@ impl PeerConnection {
     }
 
     // stream a pre-encoded file from gstreamer to avoid encoding overhead
-    pub(crate) fn file_video_source() -> RustTrackVideoSource {
+    //
+    // NOTE: `static/file.mp4` is itself H.264-encoded, so `codec` other than
+    // `VideoCodec::H264` will only decode correctly if `static/file.mp4`
+    // is replaced by a file encoded with that codec (the pipeline always
+    // reads that path); the decoder element is selected per-codec here.
+    pub(crate) fn file_video_source(codec: VideoCodec) -> RustTrackVideoSource {
         let video_source = RustTrackVideoSource::default();
         let (width, height) = (720, 480);
         video_source.start_gstreamer_thread_launch(
             & format!(
-                "filesrc location=static/file.mp4 ! qtdemux name=demux demux.video_0 ! avdec_h264 ! videoconvert ! videoscale ! video/x-raw,format=I420,width={},height={}",
+                "filesrc location=static/file.mp4 ! qtdemux name=demux demux.video_0 ! {} ! videoconvert ! videoscale ! video/x-raw,format=I420,width={},height={}",
+                codec.gst_decoder(),
                 width,
                 height,
             ),
@@ -199,6 +439,17 @@ impl PeerConnection {
 
         video_source
     }
+
+    // stream the audio pad of the same pre-encoded file, decoded and
+    // re-encoded as Opus, so offers carry a representative audio m-line
+    pub(crate) fn file_audio_source() -> RustTrackAudioSource {
+        let audio_source = RustTrackAudioSource::default();
+        audio_source.start_gstreamer_thread_launch(
+            "filesrc location=static/file.mp4 ! qtdemux name=demux demux.audio_0 ! decodebin ! audioconvert ! audioresample ! opusenc",
+        );
+
+        audio_source
+    }
 }
 
 #[cfg(test)]
@@ -207,36 +458,53 @@ pub(crate) mod tests {
     use super::*;
     use nanoid::nanoid;
 
-    pub(crate) fn peer_connection_params() -> (PeerConnectionFactory, RustTrackVideoSource) {
+    pub(crate) fn peer_connection_params() -> (
+        PeerConnectionFactory,
+        RustTrackVideoSource,
+        RustTrackAudioSource,
+    ) {
         let factory = PeerConnectionFactory::new().unwrap();
-        let video_source = PeerConnection::file_video_source();
-        (factory, video_source)
+        let video_source = PeerConnection::file_video_source(VideoCodec::H264);
+        let audio_source = PeerConnection::file_audio_source();
+        (factory, video_source, audio_source)
     }
 
     #[tokio::test]
     async fn it_creates_a_new_peer_connection() {
-        let (factory, video_source) = peer_connection_params();
-        PeerConnection::new(&factory, &video_source, nanoid!(), "new".into()).unwrap();
+        let (factory, video_source, audio_source) = peer_connection_params();
+        PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_creates_a_peer_connection_with_a_relay_only_turn_server() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let ice_config = IceConfig::turn_relay_only(
+            "turn:turn.example.com:3478".into(),
+            "load-tester".into(),
+            "secret".into(),
+        );
+
+        PeerConnection::new(&factory, &video_source, &audio_source, &ice_config, nanoid!(), "new".into()).unwrap();
     }
 
     #[tokio::test]
     async fn it_gets_stats_for_a_peer_connection() {
-        let (factory, video_source) = peer_connection_params();
-        let pc = PeerConnection::new(&factory, &video_source, nanoid!(), "new".into()).unwrap();
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
         pc.get_stats();
     }
 
     #[test]
     fn it_creates_an_offer() {
-        let (factory, video_source) = peer_connection_params();
-        let mut pc = PeerConnection::new(&factory, &video_source, nanoid!(), "new".into()).unwrap();
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
         pc.create_offer().unwrap();
     }
 
     #[test]
     fn it_creates_an_answer() {
-        let (factory, video_source) = peer_connection_params();
-        let mut pc = PeerConnection::new(&factory, &video_source, nanoid!(), "new".into()).unwrap();
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
         let offer = pc.create_offer().unwrap();
         pc.set_remote_description(offer.get_type().unwrap(), offer.to_string())
             .unwrap();
@@ -245,8 +513,8 @@ pub(crate) mod tests {
 
     #[test]
     fn it_sets_local_description() {
-        let (factory, video_source) = peer_connection_params();
-        let mut pc = PeerConnection::new(&factory, &video_source, nanoid!(), "new".into()).unwrap();
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
         let offer = pc.create_offer().unwrap();
         pc.set_local_description(offer.get_type().unwrap(), offer.to_string())
             .unwrap();
@@ -254,33 +522,98 @@ pub(crate) mod tests {
 
     #[test]
     fn it_sets_remote_description() {
-        let (factory, video_source) = peer_connection_params();
-        let mut pc = PeerConnection::new(&factory, &video_source, nanoid!(), "new".into()).unwrap();
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
         let offer = pc.create_offer().unwrap();
         pc.set_remote_description(offer.get_type().unwrap(), offer.to_string())
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn it_drains_local_ice_candidates() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
+        pc.create_offer().unwrap();
+
+        // candidate generation is async/event-driven; just exercise the
+        // drain path rather than asserting a candidate arrives.
+        let _ = tokio::time::timeout(std::time::Duration::from_millis(100), pc.next_ice_candidate()).await;
+    }
+
     #[test]
     fn it_adds_a_track() {
-        let (factory, video_source) = peer_connection_params();
-        let pc = PeerConnection::new(&factory, &video_source, nanoid!(), "new".into()).unwrap();
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
         pc.add_track(&factory, &video_source, "Testlabel".into())
             .unwrap();
     }
 
+    #[test]
+    fn it_adds_an_audio_track() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
+        pc.add_audio_track(&factory, &audio_source, "Testlabel".into())
+            .unwrap();
+    }
+
+    #[test]
+    fn it_creates_an_offer_with_audio_and_video() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
+        let offer = pc.create_offer().unwrap();
+
+        let sdp = offer.to_string();
+        assert!(sdp.contains("m=audio"));
+        assert!(sdp.contains("m=video"));
+    }
+
+    #[test]
+    fn it_sets_a_target_bitrate() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
+
+        pc.set_target_bitrate(500_000);
+    }
+
+    #[test]
+    fn it_reports_disconnected_before_any_outbound_stats_exist() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
+
+        assert_eq!(crate::reconnect::ConnectionState::Disconnected, pc.connection_state());
+    }
+
+    #[test]
+    fn it_reconnects_by_creating_and_setting_a_new_local_offer() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
+
+        pc.reconnect().unwrap();
+    }
+
     #[test]
     fn it_adds_a_transceiver() {
-        let (factory, video_source) = peer_connection_params();
-        let pc = PeerConnection::new(&factory, &video_source, nanoid!(), "new".into()).unwrap();
-        pc.add_transceiver(&factory, &video_source, "Testlabel".into())
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
+        pc.add_transceiver(&factory, &video_source, "Testlabel".into(), VideoCodec::H264)
+            .unwrap();
+    }
+
+    #[test]
+    fn it_adds_a_transceiver_preferring_vp8() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
+        pc.add_transceiver(&factory, &video_source, "Testlabel".into(), VideoCodec::Vp8)
             .unwrap();
+
+        let offer = pc.create_offer().unwrap();
+        assert!(offer.to_string().contains("VP8"));
     }
 
     // #[test]
     // fn it_does_all_the_things() {
-    //     let (factory, video_source) = peer_connection_params();
-    //     let pc = PeerConnection::new(&factory, &video_source, nanoid!(), "new".into()).unwrap();
+    //     let (factory, video_source, audio_source) = peer_connection_params();
+    //     let pc = PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "new".into()).unwrap();
     //     pc.add_transceiver().unwrap();
     // }
 }
@@ -0,0 +1,59 @@
+use crate::error::Result;
+use libwebrtc::factory::Factory;
+use libwebrtc::peer_connection::PeerConnectionFactory;
+use std::sync::Mutex;
+
+/// A small pool of pre-built `PeerConnectionFactory`s, held by `Data`, so
+/// creating a `Session` doesn't always pay factory-construction cost inline.
+///
+/// A session built via `Session::new_with_pool` holds its factory in an
+/// `Option` and returns it here when dropped, so `Drop for Session` is the
+/// release path; `release` can also be called directly to return a factory
+/// before that.
+pub(crate) struct PeerConnectionFactoryPool {
+    idle: Mutex<Vec<PeerConnectionFactory>>,
+}
+
+impl PeerConnectionFactoryPool {
+    pub(crate) fn new() -> Self {
+        Self {
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn acquire(&self) -> Result<PeerConnectionFactory> {
+        if let Some(factory) = self.idle.lock().unwrap().pop() {
+            return Ok(factory);
+        }
+
+        Factory::new().create_peer_connection_factory()
+    }
+
+    pub(crate) fn release(&self, factory: PeerConnectionFactory) {
+        self.idle.lock().unwrap().push(factory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Session;
+    use crate::signaller::SignallingMode;
+    use nanoid::nanoid;
+    use std::sync::Arc;
+
+    #[test]
+    fn it_returns_a_session_s_factory_to_the_pool_on_drop() {
+        let pool = Arc::new(PeerConnectionFactoryPool::new());
+        assert_eq!(0, pool.idle.lock().unwrap().len());
+
+        let session =
+            Session::new_with_pool(nanoid!(), "New Session".into(), pool.clone(), SignallingMode::Loopback)
+                .unwrap();
+        assert_eq!(0, pool.idle.lock().unwrap().len());
+
+        drop(session);
+
+        assert_eq!(1, pool.idle.lock().unwrap().len());
+    }
+}
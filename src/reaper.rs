@@ -0,0 +1,126 @@
+use crate::connector::{Connector, Event, EventKind};
+use crate::data::Data;
+use crate::session::{Session, State};
+use log::info;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+
+/// Timeouts governing when an idle `Session` is evicted from `Data`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReaperConfig {
+    pub(crate) scan_interval: Duration,
+    /// How long a `Stopped` session is kept around before it's collected.
+    pub(crate) stopped_cleanup_timeout: Duration,
+    /// How long a `Created` session may sit unstarted before it's collected.
+    pub(crate) created_grace_window: Duration,
+    /// How long a `Started` session may go without activity before it's
+    /// considered abandoned and collected.
+    pub(crate) idle_timeout: Duration,
+}
+
+impl Default for ReaperConfig {
+    fn default() -> Self {
+        Self {
+            scan_interval: Duration::from_secs(30),
+            stopped_cleanup_timeout: Duration::from_secs(5 * 60),
+            created_grace_window: Duration::from_secs(60),
+            idle_timeout: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+/// Spawns the background task that periodically scans `data.sessions` and
+/// removes expired sessions, bounding memory during long-running soak tests.
+pub(crate) fn spawn(data: Arc<Data>, connector: Option<Connector>, config: ReaperConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(config.scan_interval);
+
+        loop {
+            ticker.tick().await;
+            reap_once(&data, connector.as_ref(), &config);
+        }
+    })
+}
+
+fn reap_once(data: &Data, connector: Option<&Connector>, config: &ReaperConfig) {
+    let now = SystemTime::now();
+
+    let expired_ids: Vec<String> = data
+        .sessions
+        .iter()
+        .filter(|entry| is_expired(entry.value(), now, config))
+        .map(|entry| entry.key().clone())
+        .collect();
+
+    for session_id in expired_ids {
+        if let Some((_, session)) = data.sessions.remove(&session_id) {
+            info!("Reaped idle session {} (state={:?})", session_id, session.state);
+
+            if let Some(connector) = connector {
+                connector.push(Event {
+                    session_id: session_id.clone(),
+                    peer_connection_id: None,
+                    kind: EventKind::SessionReaped,
+                    timestamp: now,
+                    elapsed: session.elapsed_time(),
+                    data: json!({ "state": session.state.to_string() }),
+                });
+            }
+        }
+    }
+}
+
+fn is_expired(session: &Session, now: SystemTime, config: &ReaperConfig) -> bool {
+    let age_since = |time: SystemTime| now.duration_since(time).unwrap_or_default();
+
+    match session.state {
+        State::Stopped => session
+            .stop_time
+            .map(|stop_time| age_since(stop_time) >= config.stopped_cleanup_timeout)
+            .unwrap_or(false),
+        State::Created => age_since(session.last_activity) >= config.created_grace_window,
+        State::Started => age_since(session.last_activity) >= config.idle_timeout,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nanoid::nanoid;
+    use std::time::Duration;
+
+    #[test]
+    fn it_expires_a_created_session_past_the_grace_window() {
+        let session = Session::new(nanoid!(), "New Session".into()).unwrap();
+        let config = ReaperConfig {
+            created_grace_window: Duration::from_secs(0),
+            ..Default::default()
+        };
+
+        assert!(is_expired(&session, SystemTime::now(), &config));
+    }
+
+    #[test]
+    fn it_does_not_expire_a_fresh_created_session() {
+        let session = Session::new(nanoid!(), "New Session".into()).unwrap();
+        let config = ReaperConfig::default();
+
+        assert!(!is_expired(&session, SystemTime::now(), &config));
+    }
+
+    #[test]
+    fn it_expires_a_stopped_session_past_the_cleanup_timeout() {
+        let mut session = Session::new(nanoid!(), "New Session".into()).unwrap();
+        session.start().unwrap();
+        session.stop().unwrap();
+
+        let config = ReaperConfig {
+            stopped_cleanup_timeout: Duration::from_secs(0),
+            ..Default::default()
+        };
+
+        assert!(is_expired(&session, SystemTime::now(), &config));
+    }
+}
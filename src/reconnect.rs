@@ -0,0 +1,169 @@
+use dashmap::DashMap;
+use log::{info, warn};
+use std::time::{Duration, Instant};
+
+/// ICE/connection-state health of a single peer connection, as surfaced by
+/// `PeerConnectionManager`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::ToString)]
+pub(crate) enum ConnectionState {
+    Connected,
+    Disconnected,
+    Failed,
+}
+
+/// How a dropped peer connection is re-established.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ReconnectStrategy {
+    FixedInterval { interval: Duration, max_retries: u32 },
+    ExponentialBackoff {
+        initial_interval: Duration,
+        max_interval: Duration,
+        max_retries: u32,
+    },
+}
+
+impl Default for ReconnectStrategy {
+    fn default() -> Self {
+        Self::FixedInterval {
+            interval: Duration::from_secs(2),
+            max_retries: 5,
+        }
+    }
+}
+
+impl ReconnectStrategy {
+    pub(crate) fn max_retries(&self) -> u32 {
+        match self {
+            Self::FixedInterval { max_retries, .. } => *max_retries,
+            Self::ExponentialBackoff { max_retries, .. } => *max_retries,
+        }
+    }
+
+    /// The delay to wait before reconnect attempt number `attempt` (0-based).
+    pub(crate) fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        match self {
+            Self::FixedInterval { interval, .. } => *interval,
+            Self::ExponentialBackoff {
+                initial_interval,
+                max_interval,
+                ..
+            } => {
+                let scaled = initial_interval.saturating_mul(2u32.saturating_pow(attempt));
+                scaled.min(*max_interval)
+            }
+        }
+    }
+}
+
+/// The outcome of a single reconnect attempt for a peer connection.
+#[derive(Debug, Clone)]
+pub(crate) struct ReconnectAttempt {
+    pub(crate) attempt: u32,
+    pub(crate) succeeded: bool,
+    pub(crate) at: Instant,
+}
+
+/// Per-peer-connection connection-stability bookkeeping: current state,
+/// how many times it has dropped, and the reconnect attempts made since the
+/// last drop.
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionStability {
+    pub(crate) state: ConnectionState,
+    pub(crate) drop_count: u32,
+    pub(crate) disconnected_at: Option<Instant>,
+    pub(crate) attempts: Vec<ReconnectAttempt>,
+    pub(crate) time_to_recover: Option<Duration>,
+}
+
+impl Default for ConnectionStability {
+    fn default() -> Self {
+        Self {
+            state: ConnectionState::Connected,
+            drop_count: 0,
+            disconnected_at: None,
+            attempts: Vec::new(),
+            time_to_recover: None,
+        }
+    }
+}
+
+impl ConnectionStability {
+    /// Records an observed state transition, starting the drop clock when
+    /// the peer connection leaves `Connected`, and stopping it (recording
+    /// time-to-recover) when it returns.
+    pub(crate) fn observe(&mut self, state: ConnectionState) {
+        if self.state == ConnectionState::Connected && state != ConnectionState::Connected {
+            self.drop_count += 1;
+            self.disconnected_at = Some(Instant::now());
+            warn!("peer connection dropped (state={:?})", state);
+        }
+
+        if self.state != ConnectionState::Connected && state == ConnectionState::Connected {
+            if let Some(disconnected_at) = self.disconnected_at.take() {
+                self.time_to_recover = Some(disconnected_at.elapsed());
+                info!("peer connection recovered in {:?}", self.time_to_recover);
+            }
+            self.attempts.clear();
+        }
+
+        self.state = state;
+    }
+
+    pub(crate) fn record_attempt(&mut self, attempt: ReconnectAttempt) {
+        self.attempts.push(attempt);
+    }
+}
+
+pub(crate) type ConnectionStabilityMap = DashMap<String, ConnectionStability>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_records_a_drop_and_recovery() {
+        let mut stability = ConnectionStability::default();
+
+        stability.observe(ConnectionState::Disconnected);
+        assert_eq!(1, stability.drop_count);
+        assert!(stability.disconnected_at.is_some());
+
+        stability.observe(ConnectionState::Connected);
+        assert!(stability.time_to_recover.is_some());
+        assert!(stability.attempts.is_empty());
+    }
+
+    #[test]
+    fn it_caps_exponential_backoff_at_the_max_interval() {
+        let strategy = ReconnectStrategy::ExponentialBackoff {
+            initial_interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(1),
+            max_retries: 10,
+        };
+
+        assert_eq!(Duration::from_millis(100), strategy.delay_for_attempt(0));
+        assert_eq!(Duration::from_millis(800), strategy.delay_for_attempt(3));
+        assert_eq!(Duration::from_secs(1), strategy.delay_for_attempt(10));
+    }
+}
+
+/// Periodically calls `Session::heartbeat` for every session in `data`, on
+/// each session's own `heartbeat_interval`.
+pub(crate) fn spawn_heartbeat_sampler(
+    data: std::sync::Arc<crate::data::Data>,
+    scan_interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(scan_interval);
+
+        loop {
+            ticker.tick().await;
+
+            for session in data.sessions.iter() {
+                if let Err(err) = session.value().heartbeat().await {
+                    warn!("heartbeat failed for session {}: {}", session.key(), err);
+                }
+            }
+        }
+    })
+}
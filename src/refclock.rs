@@ -0,0 +1,292 @@
+use crate::error::{Result, ServerError};
+use log::info;
+use std::net::{Ipv4Addr, UdpSocket};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The reference clock a session's peer connections lock their RTP
+/// timestamps to, signalled in SDP per RFC 7273.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ReferenceClock {
+    System,
+    Ntp { server: String },
+    Ptp { domain: u8 },
+}
+
+impl Default for ReferenceClock {
+    fn default() -> Self {
+        Self::System
+    }
+}
+
+/// "Precise sync" configuration for a `Session`: which clock to lock to, how
+/// long to wait for convergence before `start()` fails, and the
+/// pipeline/jitterbuffer latency all peer connections should present frames
+/// against so they share one timeline.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PreciseSyncConfig {
+    pub(crate) clock: ReferenceClock,
+    pub(crate) sync_timeout: Duration,
+    pub(crate) jitterbuffer_latency: Duration,
+}
+
+impl Default for PreciseSyncConfig {
+    fn default() -> Self {
+        Self {
+            clock: ReferenceClock::default(),
+            sync_timeout: Duration::from_secs(5),
+            jitterbuffer_latency: Duration::from_millis(200),
+        }
+    }
+}
+
+/// The result of synchronizing to a reference clock: the offset from the
+/// local clock (microseconds, for `a=mediaclk:direct=`) and, for PTP, the
+/// grandmaster id (for `a=ts-refclk:ptp=...`).
+#[derive(Debug, Clone)]
+pub(crate) struct ClockSync {
+    pub(crate) offset_us: i64,
+    pub(crate) grandmaster_id: Option<String>,
+}
+
+/// Synchronizes to `clock`, failing with `ServerError::ClockSyncError` if
+/// convergence doesn't happen within `timeout`.
+pub(crate) fn synchronize(clock: &ReferenceClock, timeout: Duration) -> Result<ClockSync> {
+    let started = Instant::now();
+
+    let sync = match clock {
+        ReferenceClock::System => ClockSync {
+            offset_us: 0,
+            grandmaster_id: None,
+        },
+        ReferenceClock::Ntp { server } => sync_ntp(server, timeout)?,
+        ReferenceClock::Ptp { domain } => sync_ptp(*domain, timeout)?,
+    };
+
+    if started.elapsed() > timeout {
+        return Err(ServerError::ClockSyncError(format!(
+            "clock sync against {:?} did not converge within {:?}",
+            clock, timeout
+        )));
+    }
+
+    Ok(sync)
+}
+
+/// Seconds between the NTP epoch (1900-01-01) and the Unix epoch.
+const NTP_UNIX_EPOCH_OFFSET: u64 = 2_208_988_800;
+
+/// The local clock, encoded as an NTP 32.32 fixed-point timestamp.
+fn ntp_timestamp_now() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = now.as_secs() + NTP_UNIX_EPOCH_OFFSET;
+    let fraction = (u64::from(now.subsec_nanos()) << 32) / 1_000_000_000;
+
+    (seconds << 32) | fraction
+}
+
+/// Converts a signed NTP 32.32 fixed-point offset into microseconds.
+fn ntp_fixed_point_to_micros(value: i64) -> i64 {
+    ((value as f64 / f64::from(u32::MAX)) * 1_000_000.0).round() as i64
+}
+
+/// A minimal SNTP (RFC 2030 client-mode) round trip against `server`,
+/// bounded by `timeout`. Computes the classic `((t2-t1)+(t3-t4))/2` offset
+/// from the four client/server timestamps; a server that doesn't respond in
+/// time surfaces as a genuine `ClockSyncError` rather than a silent
+/// zero-offset success.
+fn sync_ntp(server: &str, timeout: Duration) -> Result<ClockSync> {
+    info!("Synchronizing to NTP server {}", server);
+
+    let address = if server.contains(':') {
+        server.to_owned()
+    } else {
+        format!("{}:123", server)
+    };
+
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .map_err(|e| ServerError::ClockSyncError(format!("could not open NTP client socket: {}", e)))?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .map_err(|e| ServerError::ClockSyncError(e.to_string()))?;
+    socket
+        .connect(&address)
+        .map_err(|e| ServerError::ClockSyncError(format!("could not resolve NTP server {}: {}", server, e)))?;
+
+    let mut request = [0u8; 48];
+    request[0] = 0b00_011_011; // LI = 0, VN = 3, Mode = 3 (client)
+    let t1 = ntp_timestamp_now();
+    request[40..48].copy_from_slice(&t1.to_be_bytes());
+
+    socket
+        .send(&request)
+        .map_err(|e| ServerError::ClockSyncError(format!("could not send NTP request to {}: {}", server, e)))?;
+
+    let mut response = [0u8; 48];
+    let received = socket.recv(&mut response).map_err(|e| {
+        ServerError::ClockSyncError(format!("no NTP response from {} within {:?}: {}", server, timeout, e))
+    })?;
+    let t4 = ntp_timestamp_now();
+
+    if received < 48 {
+        return Err(ServerError::ClockSyncError(format!(
+            "short NTP response from {} ({} bytes)",
+            server, received
+        )));
+    }
+
+    let t2 = u64::from_be_bytes(response[32..40].try_into().unwrap());
+    let t3 = u64::from_be_bytes(response[40..48].try_into().unwrap());
+    let offset = ((t2 as i64).wrapping_sub(t1 as i64)).wrapping_add((t3 as i64).wrapping_sub(t4 as i64)) / 2;
+
+    Ok(ClockSync {
+        offset_us: ntp_fixed_point_to_micros(offset),
+        grandmaster_id: None,
+    })
+}
+
+/// The standard PTP event-message multicast group/port (IEEE 1588-2008).
+const PTP_MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 1, 129);
+const PTP_EVENT_PORT: u16 = 319;
+
+/// Waits up to `timeout` for a PTP message of any type whose domain number
+/// is `domain` on the standard PTP multicast group's event port.
+///
+/// Binds the standard PTP event port (319), which is a privileged port on
+/// most systems -- this needs root or `CAP_NET_BIND_SERVICE` to succeed. A
+/// bind failure surfaces as a `ClockSyncError` just like a timeout does, so
+/// running unprivileged looks identical to "no grandmaster present" from the
+/// caller's side.
+///
+/// TODO: this only confirms a grandmaster is reachable on `domain` and
+/// reports a zero offset -- a real PTP client still needs to run the
+/// best-master-clock algorithm and a servo loop over repeated
+/// Sync/Follow_Up/Delay_Req/Delay_Resp exchanges to converge on an actual
+/// offset and grandmaster id.
+fn sync_ptp(domain: u8, timeout: Duration) -> Result<ClockSync> {
+    sync_ptp_on_port(domain, timeout, PTP_EVENT_PORT)
+}
+
+/// `sync_ptp`'s implementation, taking the port to bind rather than always
+/// using the real (privileged) `PTP_EVENT_PORT` -- split out so tests can
+/// exercise the timeout path on an unprivileged port instead of failing at
+/// `bind` when the test process doesn't hold `CAP_NET_BIND_SERVICE`.
+fn sync_ptp_on_port(domain: u8, timeout: Duration, port: u16) -> Result<ClockSync> {
+    info!(
+        "Listening for a PTP grandmaster on domain {} ({}:{})",
+        domain, PTP_MULTICAST_ADDR, port
+    );
+
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port))
+        .map_err(|e| ServerError::ClockSyncError(format!("could not bind PTP listener: {}", e)))?;
+    socket
+        .join_multicast_v4(&PTP_MULTICAST_ADDR, &Ipv4Addr::UNSPECIFIED)
+        .map_err(|e| ServerError::ClockSyncError(format!("could not join PTP multicast group: {}", e)))?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .map_err(|e| ServerError::ClockSyncError(e.to_string()))?;
+
+    let started = Instant::now();
+    let mut packet = [0u8; 64];
+
+    loop {
+        let (len, _) = socket.recv_from(&mut packet).map_err(|e| {
+            ServerError::ClockSyncError(format!(
+                "no PTP message for domain {} within {:?}: {}",
+                domain, timeout, e
+            ))
+        })?;
+
+        if len >= 5 && packet[4] == domain {
+            break;
+        }
+
+        if started.elapsed() > timeout {
+            return Err(ServerError::ClockSyncError(format!(
+                "no PTP message for domain {} within {:?}",
+                domain, timeout
+            )));
+        }
+    }
+
+    Ok(ClockSync {
+        offset_us: 0,
+        grandmaster_id: None,
+    })
+}
+
+/// Formats the `a=ts-refclk:` media-section line for `clock`/`sync`, per
+/// RFC 7273.
+pub(crate) fn ts_refclk_line(clock: &ReferenceClock, sync: &ClockSync) -> String {
+    match clock {
+        ReferenceClock::System => "a=ts-refclk:local".to_owned(),
+        ReferenceClock::Ntp { server } => format!("a=ts-refclk:ntp={}", server),
+        ReferenceClock::Ptp { domain } => format!(
+            "a=ts-refclk:ptp=IEEE1588-2008:{}:{}",
+            sync.grandmaster_id.as_deref().unwrap_or("0000000000000000"),
+            domain
+        ),
+    }
+}
+
+/// Formats the `a=mediaclk:direct=<offset>` media-section line for `sync`.
+pub(crate) fn mediaclk_line(sync: &ClockSync) -> String {
+    format!("a=mediaclk:direct={}", sync.offset_us)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_synchronizes_to_the_system_clock_instantly() {
+        let sync = synchronize(&ReferenceClock::System, Duration::from_secs(1)).unwrap();
+
+        assert_eq!(0, sync.offset_us);
+    }
+
+    #[test]
+    fn it_formats_an_ntp_refclk_line() {
+        let clock = ReferenceClock::Ntp {
+            server: "pool.ntp.org".into(),
+        };
+        let sync = ClockSync {
+            offset_us: 0,
+            grandmaster_id: None,
+        };
+
+        assert_eq!("a=ts-refclk:ntp=pool.ntp.org", ts_refclk_line(&clock, &sync));
+        assert_eq!("a=mediaclk:direct=0", mediaclk_line(&sync));
+    }
+
+    #[test]
+    fn it_formats_a_ptp_refclk_line() {
+        let clock = ReferenceClock::Ptp { domain: 0 };
+        let sync = ClockSync {
+            offset_us: 0,
+            grandmaster_id: None,
+        };
+
+        assert!(ts_refclk_line(&clock, &sync).starts_with("a=ts-refclk:ptp=IEEE1588-2008:"));
+    }
+
+    #[test]
+    fn it_fails_to_converge_against_an_unreachable_ntp_server() {
+        let clock = ReferenceClock::Ntp {
+            server: "203.0.113.1".into(), // TEST-NET-3 (RFC 5737): never routable
+        };
+
+        assert!(synchronize(&clock, Duration::from_millis(200)).is_err());
+    }
+
+    #[test]
+    fn it_fails_to_converge_when_no_ptp_grandmaster_is_present() {
+        // sync_ptp binds the real (privileged) PTP_EVENT_PORT, which would
+        // fail at bind() rather than timeout without CAP_NET_BIND_SERVICE --
+        // exercise sync_ptp_on_port directly on an unprivileged port instead,
+        // so this actually tests the intended "no grandmaster responded
+        // within timeout" path rather than a permission error.
+        let result = sync_ptp_on_port(0, Duration::from_millis(200), 41319);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::bitrate;
+use libwebrtc::ffi::stats_collector::Rs_VideoSenderStats;
+use serde::Serialize;
+
+/// One outbound video stream's sender-side stats: the per-stream packet
+/// counts and the loss fraction a `BitrateController` reacts to, surfaced
+/// for observability too.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct OutboundRtpStats {
+    pub(crate) packets_sent: u32,
+    pub(crate) packets_lost: i32,
+    pub(crate) fraction_lost: f64,
+}
+
+impl From<&Rs_VideoSenderStats> for OutboundRtpStats {
+    fn from(stats: &Rs_VideoSenderStats) -> Self {
+        Self {
+            packets_sent: stats.packets_sent,
+            packets_lost: stats.packets_lost,
+            fraction_lost: bitrate::loss_fraction_from_stats(std::slice::from_ref(stats)),
+        }
+    }
+}
+
+/// A single `PeerConnection`'s categorized stats, grouped the way
+/// `RTCStatsReport` differentiates report types rather than one flat list.
+///
+/// This snapshot's `libwebrtc` bindings only expose `Rs_VideoSenderStats`
+/// through `get_stats`, so `outbound_rtp` is the only category populated:
+/// `remote_inbound_rtp` (round-trip time, jitter) and `candidate_pairs` are
+/// always empty, and transport stats are not collected at all.
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct PeerConnectionStatsReport {
+    pub(crate) peer_connection_id: String,
+    pub(crate) outbound_rtp: Vec<OutboundRtpStats>,
+    pub(crate) remote_inbound_rtp: Vec<RemoteInboundRtpStats>,
+    pub(crate) candidate_pairs: Vec<CandidatePairStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RemoteInboundRtpStats {
+    pub(crate) round_trip_time_s: f64,
+    pub(crate) jitter_s: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CandidatePairStats {
+    pub(crate) state: String,
+    pub(crate) bytes_sent: u64,
+    pub(crate) bytes_received: u64,
+}
+
+/// Builds a `PeerConnectionStatsReport` from the raw `Rs_VideoSenderStats`
+/// a single `PeerConnection::get_stats()` call returns.
+pub(crate) fn report_for(peer_connection_id: &str, stats: &[Rs_VideoSenderStats]) -> PeerConnectionStatsReport {
+    PeerConnectionStatsReport {
+        peer_connection_id: peer_connection_id.to_owned(),
+        outbound_rtp: stats.iter().map(OutboundRtpStats::from).collect(),
+        ..Default::default()
+    }
+}
+
+/// An aggregated snapshot across every tracked `PeerConnection`, returned
+/// as a single report -- the primary observability surface at load-test
+/// scale. Each entry still comes from its own `PeerConnection::get_stats()`
+/// call, each with its own one-shot channel.
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct StatsReport {
+    pub(crate) peer_connections: Vec<PeerConnectionStatsReport>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sender_stats(packets_sent: u32, packets_lost: i32) -> Rs_VideoSenderStats {
+        Rs_VideoSenderStats {
+            packets_sent,
+            packets_lost,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_builds_an_outbound_rtp_report_from_sender_stats() {
+        let stats = vec![sender_stats(100, 5)];
+
+        let report = report_for("pc-1", &stats);
+
+        assert_eq!("pc-1", report.peer_connection_id);
+        assert_eq!(1, report.outbound_rtp.len());
+        assert_eq!(100, report.outbound_rtp[0].packets_sent);
+        assert_eq!(5, report.outbound_rtp[0].packets_lost);
+        assert_eq!(0.05, report.outbound_rtp[0].fraction_lost);
+    }
+
+    #[test]
+    fn it_aggregates_reports_from_multiple_peer_connections() {
+        let report = StatsReport {
+            peer_connections: vec![
+                report_for("pc-1", &[sender_stats(100, 0)]),
+                report_for("pc-2", &[sender_stats(50, 10)]),
+            ],
+        };
+
+        assert_eq!(2, report.peer_connections.len());
+        assert_eq!("pc-2", report.peer_connections[1].peer_connection_id);
+    }
+}
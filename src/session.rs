@@ -1,16 +1,22 @@
+use crate::connector::{Connector, Event, EventKind};
 use crate::error::{Result, ServerError};
 use crate::helpers::elapsed;
-use crate::peer_connection::PeerConnectionManager;
+use crate::peer_connection::{PeerConnectionManager, VideoCodec};
+use crate::pool::PeerConnectionFactoryPool;
+use crate::reconnect::{ConnectionStabilityMap, ConnectionState, ReconnectAttempt, ReconnectStrategy};
+use crate::refclock::{self, ClockSync, PreciseSyncConfig};
+use crate::signaller::{LiveKitSignaller, SignallingMode};
 use crate::stats::{get_stats, Stats};
 use core::fmt;
 use dashmap::mapref::one::Ref;
 use dashmap::DashMap;
 use libwebrtc::factory::Factory;
 use libwebrtc::peer_connection::PeerConnectionFactory;
-use libwebrtc::raw_video_frame_producer::{GStreamerRawFrameProducer, RawFrameProducer};
-use libwebrtc::video_track_source::VideoTrackSource;
-use log::{error, info};
-use std::time::SystemTime;
+use libwebrtc::rust_video_track_source::RustTrackVideoSource;
+use log::{debug, error, info};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 pub(crate) type PeerConnections = DashMap<String, PeerConnectionManager>;
 
@@ -24,13 +30,23 @@ pub(crate) enum State {
 pub(crate) struct Session {
     pub(crate) id: String,
     pub(crate) name: String,
-    pub(crate) peer_connections: PeerConnections,
-    pub(crate) video_source: VideoTrackSource,
+    pub(crate) peer_connections: Arc<PeerConnections>,
+    pub(crate) video_source: RustTrackVideoSource,
     pub(crate) state: State,
     pub(crate) start_time: Option<SystemTime>,
     pub(crate) stop_time: Option<SystemTime>,
-    pub(crate) peer_connection_factory: PeerConnectionFactory,
-    frame_producer: GStreamerRawFrameProducer,
+    peer_connection_factory: Option<PeerConnectionFactory>,
+    factory_pool: Option<Arc<PeerConnectionFactoryPool>>,
+    pub(crate) signalling_mode: SignallingMode,
+    signallers: DashMap<String, LiveKitSignaller>,
+    connector: Option<Connector>,
+    pub(crate) last_activity: SystemTime,
+    pub(crate) precise_sync: Option<PreciseSyncConfig>,
+    clock_sync: Option<ClockSync>,
+    pub(crate) reconnect_strategy: ReconnectStrategy,
+    pub(crate) heartbeat_interval: Duration,
+    last_heartbeat: std::sync::Mutex<Instant>,
+    connection_stability: ConnectionStabilityMap,
 }
 
 impl fmt::Debug for Session {
@@ -50,12 +66,49 @@ impl fmt::Debug for Session {
 
 impl Session {
     pub(crate) fn new(id: String, name: String) -> Result<Self> {
-        let peer_connections: PeerConnections = DashMap::new();
-        let (video_source, frame_producer) = PeerConnectionManager::file_video_source()?;
+        Self::new_with_signalling(id, name, SignallingMode::Loopback)
+    }
+
+    pub(crate) fn new_with_signalling(
+        id: String,
+        name: String,
+        signalling_mode: SignallingMode,
+    ) -> Result<Self> {
         let factory = Factory::new();
         let peer_connection_factory = factory.create_peer_connection_factory()?;
 
-        Ok(Self {
+        Self::new_with_factory(id, name, peer_connection_factory, signalling_mode)
+    }
+
+    /// Builds a session by acquiring a `PeerConnectionFactory` from
+    /// `factory_pool` rather than constructing one inline, returning it to
+    /// the pool when the session is dropped instead of discarding it.
+    pub(crate) fn new_with_pool(
+        id: String,
+        name: String,
+        factory_pool: Arc<PeerConnectionFactoryPool>,
+        signalling_mode: SignallingMode,
+    ) -> Result<Self> {
+        let peer_connection_factory = factory_pool.acquire()?;
+
+        let mut session = Self::new_with_factory(id, name, peer_connection_factory, signalling_mode)?;
+        session.factory_pool = Some(factory_pool);
+
+        Ok(session)
+    }
+
+    /// Builds a session from an already-acquired `PeerConnectionFactory`,
+    /// e.g. one handed out by `PeerConnectionFactoryPool::acquire`.
+    pub(crate) fn new_with_factory(
+        id: String,
+        name: String,
+        peer_connection_factory: PeerConnectionFactory,
+        signalling_mode: SignallingMode,
+    ) -> Result<Self> {
+        let peer_connections: Arc<PeerConnections> = Arc::new(DashMap::new());
+        let video_source = PeerConnectionManager::file_video_source(VideoCodec::H264);
+
+        let session = Self {
             id,
             name,
             peer_connections,
@@ -63,9 +116,183 @@ impl Session {
             state: State::Created,
             start_time: None,
             stop_time: None,
-            peer_connection_factory,
-            frame_producer,
-        })
+            peer_connection_factory: Some(peer_connection_factory),
+            factory_pool: None,
+            signalling_mode,
+            signallers: DashMap::new(),
+            connector: None,
+            last_activity: SystemTime::now(),
+            precise_sync: None,
+            clock_sync: None,
+            reconnect_strategy: ReconnectStrategy::default(),
+            heartbeat_interval: Duration::from_secs(5),
+            last_heartbeat: std::sync::Mutex::new(Instant::now()),
+            connection_stability: DashMap::new(),
+        };
+
+        session.push_event(EventKind::SessionCreated, None, json!({ "name": session.name }));
+
+        Ok(session)
+    }
+
+    /// Attaches a connector so subsequent state transitions and stats
+    /// snapshots are persisted.
+    pub(crate) fn attach_connector(&mut self, connector: Connector) {
+        self.connector = Some(connector);
+    }
+
+    /// Enables "precise sync" mode: `start()` will synchronize to
+    /// `config.clock` before returning, and peer connections should present
+    /// frames with `config.jitterbuffer_latency` against that timeline.
+    pub(crate) fn configure_precise_sync(&mut self, config: PreciseSyncConfig) {
+        self.precise_sync = Some(config);
+    }
+
+    /// Configures how dropped peer connections are re-established and how
+    /// often `heartbeat` should be run against them.
+    pub(crate) fn configure_reconnect(&mut self, strategy: ReconnectStrategy, heartbeat_interval: Duration) {
+        self.reconnect_strategy = strategy;
+        self.heartbeat_interval = heartbeat_interval;
+    }
+
+    /// Checks each peer connection's ICE/connection-state health, recording
+    /// drops and recoveries, and attempts to re-establish any that are
+    /// disconnected/failed per `reconnect_strategy`.
+    pub(crate) async fn heartbeat(&self) -> Result<()> {
+        {
+            let mut last_heartbeat = self.last_heartbeat.lock().unwrap();
+            if last_heartbeat.elapsed() < self.heartbeat_interval {
+                return Ok(());
+            }
+            *last_heartbeat = Instant::now();
+        }
+
+        let snapshot: Vec<(String, ConnectionState)> = self
+            .peer_connections
+            .iter()
+            .map(|pc| (pc.key().clone(), pc.value().connection_state()))
+            .collect();
+
+        let mut to_reconnect = Vec::new();
+
+        for (peer_connection_id, state) in snapshot {
+            self.connection_stability
+                .entry(peer_connection_id.clone())
+                .or_default()
+                .observe(state);
+
+            if !matches!(state, ConnectionState::Disconnected | ConnectionState::Failed) {
+                continue;
+            }
+
+            let attempt = self
+                .connection_stability
+                .get(&peer_connection_id)
+                .map(|stability| stability.attempts.len() as u32)
+                .unwrap_or(0);
+
+            if attempt >= self.reconnect_strategy.max_retries() {
+                continue;
+            }
+
+            to_reconnect.push((peer_connection_id, attempt));
+        }
+
+        // Run every disconnected connection's backoff delay + reconnect
+        // attempt concurrently -- sequentially awaiting them here would
+        // serialize the whole tick behind attempt_count x delay once enough
+        // peer connections drop together for one heartbeat interval to see
+        // more than one of them disconnected at once.
+        futures_util::future::join_all(
+            to_reconnect
+                .into_iter()
+                .map(|(peer_connection_id, attempt)| self.reconnect_one(peer_connection_id, attempt)),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    /// Backs off per `reconnect_strategy`, attempts to reconnect one peer
+    /// connection, and records the outcome -- split out of `heartbeat` so
+    /// callers can run it concurrently across every disconnected connection.
+    async fn reconnect_one(&self, peer_connection_id: String, attempt: u32) {
+        tokio::time::sleep(self.reconnect_strategy.delay_for_attempt(attempt)).await;
+
+        let succeeded = self
+            .peer_connections
+            .get_mut(&peer_connection_id)
+            .map(|mut pc| pc.reconnect().is_ok())
+            .unwrap_or(false);
+
+        info!(
+            "Reconnect attempt {} for peer connection {} in session {}: succeeded={}",
+            attempt, peer_connection_id, self.id, succeeded
+        );
+
+        if let Some(mut stability) = self.connection_stability.get_mut(&peer_connection_id) {
+            stability.record_attempt(ReconnectAttempt {
+                attempt,
+                succeeded,
+                at: Instant::now(),
+            });
+        }
+    }
+
+    /// Summarizes drop count / time-to-recover per peer connection, merged
+    /// into the `StatsSnapshot` event `get_stats` pushes to the connector
+    /// for reporting connection stability alongside throughput. The `Stats`
+    /// value `get_stats` returns does not carry it.
+    fn connection_stability_summary(&self) -> serde_json::Value {
+        let summary: serde_json::Map<String, serde_json::Value> = self
+            .connection_stability
+            .iter()
+            .map(|entry| {
+                let stability = entry.value();
+                (
+                    entry.key().clone(),
+                    json!({
+                        "state": stability.state.to_string(),
+                        "drop_count": stability.drop_count,
+                        "reconnect_attempts": stability.attempts.len(),
+                        "time_to_recover_ms": stability.time_to_recover.map(|d| d.as_millis() as u64),
+                    }),
+                )
+            })
+            .collect();
+
+        serde_json::Value::Object(summary)
+    }
+
+    /// The `a=ts-refclk:`/`a=mediaclk:direct=` SDP lines peer connections
+    /// should emit on their media sections, once precise sync has
+    /// converged.
+    pub(crate) fn clock_sync_sdp_lines(&self) -> Vec<String> {
+        match (&self.precise_sync, &self.clock_sync) {
+            (Some(precise_sync), Some(clock_sync)) => vec![
+                refclock::ts_refclk_line(&precise_sync.clock, clock_sync),
+                refclock::mediaclk_line(clock_sync),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    fn push_event(
+        &self,
+        kind: EventKind,
+        peer_connection_id: Option<String>,
+        data: serde_json::Value,
+    ) {
+        if let Some(connector) = &self.connector {
+            connector.push(Event {
+                session_id: self.id.clone(),
+                peer_connection_id,
+                kind,
+                timestamp: SystemTime::now(),
+                elapsed: self.elapsed_time(),
+                data,
+            });
+        }
     }
 
     pub(crate) fn start(&mut self) -> Result<()> {
@@ -77,11 +304,21 @@ impl Session {
             ));
         }
 
+        if let Some(precise_sync) = &self.precise_sync {
+            info!(
+                "Synchronizing session {} to reference clock {:?}",
+                self.id, precise_sync.clock
+            );
+            self.clock_sync = Some(refclock::synchronize(&precise_sync.clock, precise_sync.sync_timeout)?);
+        }
+
         self.state = State::Started;
         self.start_time = Some(SystemTime::now());
 
         info!("Started session: {:?}", self);
 
+        self.push_event(EventKind::SessionStarted, None, json!({}));
+
         Ok(())
     }
 
@@ -99,17 +336,16 @@ impl Session {
 
         info!("stopped session: {:?}", self);
 
+        self.push_event(EventKind::SessionStopped, None, json!({}));
+
         Ok(())
     }
 
     pub(crate) async fn peer_connection_stats(&self) {
         for pc in self.peer_connections.iter() {
-            match pc.value().export_stats(&self.id.to_owned()).await {
-                Ok(_) => {}
-                Err(err) => {
-                    error!("Failed to export stats for peer connection: {}", err);
-                }
-            }
+            let report = pc.value().stats_report();
+            let data = serde_json::to_value(&report).unwrap_or_default();
+            self.push_event(EventKind::StatsSnapshot, Some(pc.key().clone()), data);
         }
     }
 
@@ -120,16 +356,45 @@ impl Session {
 
         info!("Stats for session {}: {:?}", self.id, stats);
 
+        let mut stats_value = serde_json::to_value(&stats).unwrap_or_default();
+        if let serde_json::Value::Object(ref mut map) = stats_value {
+            map.insert(
+                "connection_stability".to_owned(),
+                self.connection_stability_summary(),
+            );
+        }
+        self.push_event(EventKind::StatsSnapshot, None, stats_value);
+
         Ok(stats)
     }
 
-    pub(crate) fn add_peer_connection(&self, peer_connection: PeerConnectionManager) -> Result<()> {
+    pub(crate) async fn add_peer_connection(
+        &self,
+        mut peer_connection: PeerConnectionManager,
+    ) -> Result<()> {
         info!(
             "Attempting to add peer connection {} for session {}",
             peer_connection.id, self.id
         );
         let peer_connection_id = peer_connection.id.clone();
 
+        if let SignallingMode::LiveKit(config) = &self.signalling_mode {
+            let (trickle_tx, trickle_rx) = tokio::sync::mpsc::unbounded_channel();
+            let signaller = LiveKitSignaller::connect(
+                config,
+                &mut peer_connection,
+                peer_connection_id.clone(),
+                self.name.clone(),
+                trickle_tx,
+            )
+            .await?;
+            self.signallers.insert(peer_connection_id.clone(), signaller);
+            tokio::spawn(Self::apply_trickled_candidates(
+                Arc::clone(&self.peer_connections),
+                trickle_rx,
+            ));
+        }
+
         self.peer_connections
             .insert(peer_connection_id.clone(), peer_connection);
 
@@ -138,6 +403,58 @@ impl Session {
             &peer_connection_id, &self.id
         );
 
+        self.push_event(
+            EventKind::PeerConnectionAdded,
+            Some(peer_connection_id),
+            json!({}),
+        );
+
+        Ok(())
+    }
+
+    /// Drains `trickle_rx` for the lifetime of a LiveKit-signalled peer
+    /// connection, applying each candidate to its owner in `peer_connections`
+    /// by id. Runs detached from `add_peer_connection`'s `&self` borrow, so it
+    /// takes an `Arc` clone of the map rather than capturing `self`.
+    async fn apply_trickled_candidates(
+        peer_connections: Arc<PeerConnections>,
+        mut trickle_rx: tokio::sync::mpsc::UnboundedReceiver<crate::signaller::TrickleCandidate>,
+    ) {
+        while let Some(candidate) = trickle_rx.recv().await {
+            match peer_connections.get(&candidate.peer_connection_id) {
+                Some(pc) => {
+                    if let Err(err) =
+                        pc.add_ice_candidate(candidate.candidate, candidate.sdp_mid, candidate.sdp_mline_index)
+                    {
+                        error!(
+                            "failed to apply trickled ICE candidate for {}: {}",
+                            candidate.peer_connection_id, err
+                        );
+                    }
+                }
+                None => debug!(
+                    "dropping trickled ICE candidate for unknown peer connection {}",
+                    candidate.peer_connection_id
+                ),
+            }
+        }
+    }
+
+    pub(crate) fn remove_peer_connection(&self, id: &str) -> Result<()> {
+        info!(
+            "Attempting to remove peer connection {} from session {}",
+            id, self.id
+        );
+
+        self.peer_connections.remove(id).ok_or_else(|| {
+            ServerError::InvalidPeerConnection(format!("Peer connection {} not found", id))
+        })?;
+        if let Some((_, signaller)) = self.signallers.remove(id) {
+            signaller.cancel();
+        }
+
+        info!("Removed peer connection {} from session {}", id, self.id);
+
         Ok(())
     }
 
@@ -155,6 +472,14 @@ impl Session {
         })?)
     }
 
+    /// Records activity on the session so an idle-but-started session isn't
+    /// reaped out from under an in-progress load test.
+    pub(crate) fn touch(&mut self) -> Result<()> {
+        self.last_activity = SystemTime::now();
+
+        Ok(())
+    }
+
     pub(crate) fn elapsed_time(&self) -> Option<u64> {
         match self.state {
             State::Created => None,
@@ -166,7 +491,13 @@ impl Session {
 
 impl Drop for Session {
     fn drop(&mut self) {
-        self.frame_producer.cancel();
+        for signaller in self.signallers.iter() {
+            signaller.value().cancel();
+        }
+
+        if let (Some(pool), Some(factory)) = (self.factory_pool.take(), self.peer_connection_factory.take()) {
+            pool.release(factory);
+        }
     }
 }
 
@@ -260,6 +591,63 @@ mod tests {
         assert_eq!(State::Stopped, session.state);
     }
 
+    #[test]
+    fn it_touches_a_session() {
+        let mut session = Session::new(nanoid!(), "New Session".into()).unwrap();
+        let last_activity = session.last_activity;
+
+        std::thread::sleep(time::Duration::from_millis(10));
+        session.touch().unwrap();
+
+        assert!(session.last_activity > last_activity);
+    }
+
+    #[test]
+    fn it_synchronizes_a_precise_sync_session_on_start() {
+        let mut session = Session::new(nanoid!(), "New Session".into()).unwrap();
+        session.configure_precise_sync(crate::refclock::PreciseSyncConfig {
+            clock: crate::refclock::ReferenceClock::System,
+            ..Default::default()
+        });
+
+        session.start().unwrap();
+
+        assert_eq!(
+            vec!["a=ts-refclk:local".to_owned(), "a=mediaclk:direct=0".to_owned()],
+            session.clock_sync_sdp_lines()
+        );
+    }
+
+    #[test]
+    fn it_fails_to_start_a_precise_sync_session_when_clock_sync_cannot_converge() {
+        let mut session = Session::new(nanoid!(), "New Session".into()).unwrap();
+        session.configure_precise_sync(crate::refclock::PreciseSyncConfig {
+            clock: crate::refclock::ReferenceClock::Ntp {
+                server: "203.0.113.1".into(), // TEST-NET-3 (RFC 5737): never routable
+            },
+            sync_timeout: time::Duration::from_millis(200),
+            ..Default::default()
+        });
+
+        assert!(session.start().is_err());
+    }
+
+    #[tokio::test]
+    async fn it_pushes_events_to_an_attached_connector() {
+        let (connector, _join_handle) = crate::connector::Connector::connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        let mut session = Session::new(nanoid!(), "New Session".into()).unwrap();
+        session.attach_connector(connector);
+
+        session.start().unwrap();
+        session.stop().unwrap();
+
+        // no assertion beyond "doesn't panic": the connector writes are
+        // fire-and-forget from the session's perspective.
+    }
+
     #[tokio::test]
     async fn it_gets_stats() {
         let session = Session::new(nanoid!(), "New Session".into()).unwrap();
@@ -275,10 +663,10 @@ mod tests {
         assert!(stats.is_ok());
     }
 
-    #[test]
-    fn it_creates_a_peer_connection() {
+    #[tokio::test]
+    async fn it_creates_a_peer_connection() {
         tracing_subscriber::fmt::init();
-        let (_api, factory, _video_source) = peer_connection_params();
+        let (factory, video_source, audio_source) = peer_connection_params();
         let session = Session::new(nanoid!(), "New Session".into()).unwrap();
         let session_id = session.id.clone();
         let data = Data::new();
@@ -289,11 +677,133 @@ mod tests {
 
         let pc_id = nanoid!();
         {
-            let pc = PeerConnectionManager::new(&factory, pc_id.clone(), "new".into()).unwrap();
-            session.add_peer_connection(pc).unwrap();
+            let pc = PeerConnectionManager::new(
+                &factory,
+                &video_source,
+                &audio_source,
+                &crate::peer_connection::IceConfig::default(),
+                pc_id.clone(),
+                "new".into(),
+            )
+            .unwrap();
+            session.add_peer_connection(pc).await.unwrap();
 
             assert_eq!(session.peer_connections.get(&pc_id).unwrap().id, pc_id);
             std::thread::sleep(time::Duration::from_millis(1000));
         }
     }
+
+    #[tokio::test]
+    async fn it_connects_a_peer_connection_to_livekit() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let config = crate::signaller::LiveKitConfig {
+            ws_url: "ws://127.0.0.1:7880".into(),
+            api_key: "devkey".into(),
+            secret_key: "secret".into(),
+            room_name: "load-test".into(),
+        };
+        let session =
+            Session::new_with_signalling(nanoid!(), "New Session".into(), SignallingMode::LiveKit(config))
+                .unwrap();
+        let session_id = session.id.clone();
+        let data = Data::new();
+        data.add_session(session).unwrap();
+
+        let session = &mut *data.sessions.get_mut(&session_id).unwrap();
+        session.start().unwrap();
+
+        let pc = PeerConnectionManager::new(
+            &factory,
+            &video_source,
+            &audio_source,
+            &crate::peer_connection::IceConfig::default(),
+            nanoid!(),
+            "new".into(),
+        )
+        .unwrap();
+
+        // no LiveKit server is running in this test environment, so connecting
+        // the signaller is expected to fail rather than hang.
+        assert!(session.add_peer_connection(pc).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_applies_a_trickled_candidate_to_its_peer_connection() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let peer_connections: Arc<PeerConnections> = Arc::new(DashMap::new());
+        let pc_id = nanoid!();
+        let pc = PeerConnectionManager::new(
+            &factory,
+            &video_source,
+            &audio_source,
+            &crate::peer_connection::IceConfig::default(),
+            pc_id.clone(),
+            "new".into(),
+        )
+        .unwrap();
+        peer_connections.insert(pc_id.clone(), pc);
+
+        let (trickle_tx, trickle_rx) = tokio::sync::mpsc::unbounded_channel();
+        let apply_handle = tokio::spawn(Session::apply_trickled_candidates(
+            Arc::clone(&peer_connections),
+            trickle_rx,
+        ));
+
+        trickle_tx
+            .send(crate::signaller::TrickleCandidate {
+                peer_connection_id: pc_id,
+                candidate: "candidate:1 1 UDP 1 127.0.0.1 9 typ host".into(),
+                sdp_mid: "0".into(),
+                sdp_mline_index: 0,
+            })
+            .unwrap();
+
+        // an unknown peer connection id is dropped rather than panicking.
+        trickle_tx
+            .send(crate::signaller::TrickleCandidate {
+                peer_connection_id: nanoid!(),
+                candidate: "candidate:1 1 UDP 1 127.0.0.1 9 typ host".into(),
+                sdp_mid: "0".into(),
+                sdp_mline_index: 0,
+            })
+            .unwrap();
+
+        drop(trickle_tx);
+        apply_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_runs_reconnect_attempts_for_multiple_disconnected_connections_concurrently() {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut session = Session::new(nanoid!(), "New Session".into()).unwrap();
+        session.configure_reconnect(
+            ReconnectStrategy::FixedInterval {
+                interval: time::Duration::from_millis(200),
+                max_retries: 5,
+            },
+            time::Duration::ZERO,
+        );
+
+        for _ in 0..3 {
+            let pc = PeerConnectionManager::new(
+                &factory,
+                &video_source,
+                &audio_source,
+                &crate::peer_connection::IceConfig::default(),
+                nanoid!(),
+                "new".into(),
+            )
+            .unwrap();
+            session.peer_connections.insert(pc.id.clone(), pc);
+        }
+
+        let started = Instant::now();
+        session.heartbeat().await.unwrap();
+        let elapsed = started.elapsed();
+
+        // sequentially, 3 disconnected connections backing off 200ms each
+        // would take ~600ms; run concurrently, one heartbeat tick should
+        // take roughly one delay's worth of time.
+        assert!(elapsed < time::Duration::from_millis(500), "heartbeat took {:?}", elapsed);
+    }
 }
@@ -0,0 +1,270 @@
+use crate::error::{Result, ServerError};
+use futures_util::{SinkExt, StreamExt};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use libwebrtc::ffi::sdp::SdpType;
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+const ACCESS_TOKEN_TTL_SECS: u64 = 6 * 60 * 60;
+/// How long to wait for LiveKit to answer our offer before giving up.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(10);
+
+type WsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Messages we send to the LiveKit room's join WebSocket.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OutgoingSignal {
+    Offer { sdp: String },
+}
+
+/// Messages LiveKit's join WebSocket sends back: a `join` acknowledgement,
+/// our offer's `answer`, and `trickle` messages carrying the remote side's
+/// ICE candidates.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum IncomingSignal {
+    Join,
+    Answer {
+        sdp: String,
+    },
+    Trickle {
+        candidate: String,
+        sdp_mid: String,
+        sdp_mline_index: u32,
+    },
+}
+
+/// A remote ICE candidate trickled in over a LiveKit join WebSocket, handed
+/// off so the receiving end can apply it to the owning peer connection
+/// (`run` only has the raw websocket, not a handle back to the connection).
+#[derive(Debug)]
+pub(crate) struct TrickleCandidate {
+    pub(crate) peer_connection_id: String,
+    pub(crate) candidate: String,
+    pub(crate) sdp_mid: String,
+    pub(crate) sdp_mline_index: u32,
+}
+
+/// Selects how a `Session` exchanges SDP/ICE for its peer connections: against
+/// our own loopback handlers, or against a real LiveKit room.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SignallingMode {
+    Loopback,
+    LiveKit(LiveKitConfig),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LiveKitConfig {
+    pub(crate) ws_url: String,
+    pub(crate) api_key: String,
+    pub(crate) secret_key: String,
+    pub(crate) room_name: String,
+}
+
+#[derive(Serialize)]
+struct VideoGrant {
+    room: String,
+    #[serde(rename = "roomJoin")]
+    room_join: bool,
+    #[serde(rename = "canPublish")]
+    can_publish: bool,
+    #[serde(rename = "canSubscribe")]
+    can_subscribe: bool,
+}
+
+#[derive(Serialize)]
+struct Claims {
+    iss: String,
+    exp: u64,
+    nbf: u64,
+    sub: String,
+    name: String,
+    video: VideoGrant,
+}
+
+/// Mints a LiveKit access token: a JWT signed HMAC-SHA256 with video-publish
+/// grants for `room_name`, scoped to `identity`.
+fn mint_access_token(config: &LiveKitConfig, identity: &str, participant_name: &str) -> Result<String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| ServerError::SignallingError(e.to_string()))?
+        .as_secs();
+
+    let claims = Claims {
+        iss: config.api_key.clone(),
+        exp: now + ACCESS_TOKEN_TTL_SECS,
+        nbf: now,
+        sub: identity.to_owned(),
+        name: participant_name.to_owned(),
+        video: VideoGrant {
+            room: config.room_name.clone(),
+            room_join: true,
+            can_publish: true,
+            can_subscribe: true,
+        },
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config.secret_key.as_bytes()),
+    )
+    .map_err(|e| ServerError::SignallingError(e.to_string()))
+}
+
+/// Drives a single peer connection's offer/answer exchange against a
+/// LiveKit room over its join WebSocket and relays the room's trickled ICE
+/// candidates back; locally gathered candidates are not sent to the room.
+pub(crate) struct LiveKitSignaller {
+    join_handle: JoinHandle<()>,
+}
+
+impl LiveKitSignaller {
+    /// Connects to the LiveKit room's join WebSocket, drives the
+    /// offer/answer exchange against `peer_connection` (creating our offer,
+    /// sending it, and applying the room's answer), then hands the
+    /// connection off to a background task that keeps relaying trickled ICE
+    /// candidates onto `trickle_tx` for the rest of the peer connection's
+    /// lifetime -- the caller is expected to drain `trickle_tx`'s receiver
+    /// and apply each candidate to the peer connection it names, since this
+    /// task only has the raw websocket, not a handle back to the connection.
+    pub(crate) async fn connect(
+        config: &LiveKitConfig,
+        peer_connection: &mut crate::peer_connection::PeerConnection,
+        peer_connection_id: String,
+        participant_name: String,
+        trickle_tx: UnboundedSender<TrickleCandidate>,
+    ) -> Result<Self> {
+        let token = mint_access_token(config, &peer_connection_id, &participant_name)?;
+        let url = format!("{}/rtc?access_token={}&auto_subscribe=true", config.ws_url, token);
+
+        info!(
+            "Connecting peer connection {} to LiveKit room {}",
+            peer_connection_id, config.room_name
+        );
+
+        let (mut ws_stream, _) = connect_async(url)
+            .await
+            .map_err(|e| ServerError::SignallingError(e.to_string()))?;
+
+        let offer = peer_connection.create_offer()?;
+        peer_connection.set_local_description(SdpType::Offer, offer.to_string())?;
+
+        Self::send_signal(&mut ws_stream, &OutgoingSignal::Offer { sdp: offer.to_string() }).await?;
+
+        let answer_sdp = Self::await_answer(&mut ws_stream, &peer_connection_id).await?;
+        peer_connection.set_remote_description(SdpType::Answer, answer_sdp)?;
+
+        info!(
+            "Peer connection {} joined LiveKit room {}",
+            peer_connection_id, config.room_name
+        );
+
+        let join_handle = tokio::spawn(Self::run(peer_connection_id, ws_stream, trickle_tx));
+
+        Ok(Self { join_handle })
+    }
+
+    async fn send_signal(ws_stream: &mut WsStream, signal: &OutgoingSignal) -> Result<()> {
+        let text = serde_json::to_string(signal).map_err(|e| ServerError::SignallingError(e.to_string()))?;
+
+        ws_stream
+            .send(Message::Text(text))
+            .await
+            .map_err(|e| ServerError::SignallingError(e.to_string()))
+    }
+
+    /// Reads signal messages until the room answers our offer, and gives up
+    /// after `ANSWER_TIMEOUT`. Any `join`/`trickle` messages seen along the
+    /// way are logged and then dropped; candidates trickled before the
+    /// answer are not relayed onto `trickle_tx`.
+    async fn await_answer(ws_stream: &mut WsStream, peer_connection_id: &str) -> Result<String> {
+        tokio::time::timeout(ANSWER_TIMEOUT, async {
+            loop {
+                match ws_stream.next().await {
+                    Some(Ok(Message::Text(text))) => match serde_json::from_str::<IncomingSignal>(&text) {
+                        Ok(IncomingSignal::Answer { sdp }) => return Ok(sdp),
+                        Ok(signal) => debug!(
+                            "livekit signal for {} while awaiting answer: {:?}",
+                            peer_connection_id, signal
+                        ),
+                        Err(err) => debug!(
+                            "unrecognized livekit signal for {}: {} ({})",
+                            peer_connection_id, text, err
+                        ),
+                    },
+                    Some(Ok(Message::Close(_))) | None => {
+                        return Err(ServerError::SignallingError(format!(
+                            "livekit connection closed for {} before answering our offer",
+                            peer_connection_id
+                        )))
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => return Err(ServerError::SignallingError(err.to_string())),
+                }
+            }
+        })
+        .await
+        .map_err(|_| {
+            ServerError::SignallingError(format!(
+                "timed out waiting for a LiveKit answer for {}",
+                peer_connection_id
+            ))
+        })?
+    }
+
+    async fn run(peer_connection_id: String, mut ws_stream: WsStream, trickle_tx: UnboundedSender<TrickleCandidate>) {
+        while let Some(message) = ws_stream.next().await {
+            match message {
+                Ok(Message::Text(text)) => match serde_json::from_str::<IncomingSignal>(&text) {
+                    Ok(IncomingSignal::Trickle {
+                        candidate,
+                        sdp_mid,
+                        sdp_mline_index,
+                    }) => {
+                        debug!(
+                            "livekit trickle candidate for {}: {} (mid={}, mline={})",
+                            peer_connection_id, candidate, sdp_mid, sdp_mline_index
+                        );
+
+                        // the receiving end may have gone away (e.g. the
+                        // session was torn down); nothing to do but drop it.
+                        let _ = trickle_tx.send(TrickleCandidate {
+                            peer_connection_id: peer_connection_id.clone(),
+                            candidate,
+                            sdp_mid,
+                            sdp_mline_index,
+                        });
+                    }
+                    Ok(signal) => debug!("livekit signal for {}: {:?}", peer_connection_id, signal),
+                    Err(err) => debug!(
+                        "unrecognized livekit signal for {}: {} ({})",
+                        peer_connection_id, text, err
+                    ),
+                },
+                Ok(Message::Close(_)) => {
+                    info!("livekit signal connection closed for {}", peer_connection_id);
+                    break;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    error!("livekit signal connection error for {}: {}", peer_connection_id, err);
+                    break;
+                }
+            }
+        }
+
+        let _ = ws_stream.close(None).await;
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.join_handle.abort();
+    }
+}
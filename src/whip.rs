@@ -0,0 +1,112 @@
+use crate::data::SharedState;
+use crate::error::{Result, ServerError};
+use crate::peer_connection::{IceConfig, PeerConnection, PeerConnectionManager};
+use crate::{call_peer_connection, call_session, get_session_attribute};
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, post};
+use axum::{Router, Server};
+use libwebrtc::ffi::sdp::SdpType;
+use log::info;
+use std::net::SocketAddr;
+
+const SDP_CONTENT_TYPE: &str = "application/sdp";
+
+/// Serves the WHIP front end on its own port alongside the gRPC `WebRtc`
+/// service, sharing the same [`SharedState`]/`Data`.
+pub(crate) async fn serve(addr: SocketAddr, shared_state: SharedState) -> Result<()> {
+    let app = router(shared_state);
+
+    info!("Starting WHIP server on {}", addr);
+
+    Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .map_err(|e| ServerError::WhipError(e.to_string()))
+}
+
+fn router(shared_state: SharedState) -> Router {
+    Router::new()
+        .route("/whip/:session_id", post(create_peer_connection))
+        .route("/whip/:session_id/:peer_connection_id", delete(delete_peer_connection))
+        .with_state(shared_state)
+}
+
+/// `POST /whip/{session_id}`: accepts an SDP offer, creates a peer
+/// connection for the session, and answers with the SDP + a `Location`
+/// resource URL, mirroring `create_peer_connection`/`create_answer` over
+/// gRPC.
+async fn create_peer_connection(
+    State(shared_state): State<SharedState>,
+    Path(session_id): Path<String>,
+    offer_sdp: String,
+) -> std::result::Result<Response, ServerError> {
+    let peer_connection_id = nanoid::nanoid!();
+
+    // TODO: same proto-absence constraint as create_peer_connection's gRPC
+    // handler (handlers.rs) -- IceConfig::default() is the best we can do
+    // until WHIP gets a way to carry TURN/relay-only configuration.
+    let audio_source = PeerConnection::file_audio_source();
+    let peer_connection = PeerConnectionManager::new(
+        &shared_state.peer_connection_factory,
+        &get_session_attribute!(shared_state, session_id.clone(), video_source),
+        &audio_source,
+        &IceConfig::default(),
+        peer_connection_id.clone(),
+        "whip".into(),
+    )?;
+
+    call_session!(shared_state, session_id, add_peer_connection, peer_connection).await?;
+
+    call_peer_connection!(
+        shared_state,
+        session_id,
+        peer_connection_id,
+        set_remote_description,
+        SdpType::Offer,
+        offer_sdp
+    )?;
+
+    let answer = call_peer_connection!(shared_state, session_id, peer_connection_id, create_answer)?;
+
+    call_peer_connection!(
+        shared_state,
+        session_id,
+        peer_connection_id,
+        set_local_description,
+        SdpType::Answer,
+        answer.to_string()
+    )?;
+
+    let location = format!("/whip/{}/{}", session_id, peer_connection_id);
+
+    info!("Created WHIP peer connection {} for session {}", peer_connection_id, session_id);
+
+    let mut response = (StatusCode::CREATED, answer.to_string()).into_response();
+    response
+        .headers_mut()
+        .insert(header::LOCATION, HeaderValue::from_str(&location).map_err(|e| ServerError::WhipError(e.to_string()))?);
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(SDP_CONTENT_TYPE));
+
+    Ok(response)
+}
+
+/// `DELETE /whip/{session_id}/{peer_connection_id}`: tears down the peer
+/// connection created by the matching `POST`.
+async fn delete_peer_connection(
+    State(shared_state): State<SharedState>,
+    Path((session_id, peer_connection_id)): Path<(String, String)>,
+) -> std::result::Result<StatusCode, ServerError> {
+    call_session!(shared_state, session_id, remove_peer_connection, &peer_connection_id)?;
+
+    Ok(StatusCode::OK)
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
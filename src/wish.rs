@@ -0,0 +1,273 @@
+use crate::error::{Result, ServerError};
+use crate::peer_connection::{IceConfig, PeerConnection};
+use axum::extract::{Path, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, patch, post};
+use axum::{Json, Router, Server};
+use dashmap::DashMap;
+use libwebrtc::ffi::sdp::SdpType;
+use libwebrtc::peerconnection_factory::PeerConnectionFactory;
+use libwebrtc::rust_audio_track_source::RustTrackAudioSource;
+use libwebrtc::rust_video_track_source::RustTrackVideoSource;
+use log::info;
+use nanoid::nanoid;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const SDP_CONTENT_TYPE: &str = "application/sdp";
+
+/// Shared state for the standalone WHIP/WHEP subsystem: one
+/// `PeerConnectionFactory` and video source producing the connections
+/// created over HTTP, keyed by a resource id so `DELETE`/`PATCH` can find
+/// them again.
+///
+/// This is a second, intentionally `Session`-free HTTP front end alongside
+/// `whip.rs`'s session-backed one: it tracks bare resources rather than
+/// `Session` peer connections, so a load test can drive plain HTTP
+/// publishers/subscribers (and WHEP egress, which `whip.rs` doesn't cover)
+/// without paying for gRPC `Session` bookkeeping (heartbeat, reconnect,
+/// precise sync) that a bare resource doesn't need.
+#[derive(Clone)]
+pub(crate) struct WishState {
+    inner: Arc<WishStateInner>,
+}
+
+struct WishStateInner {
+    peer_connection_factory: PeerConnectionFactory,
+    video_source: RustTrackVideoSource,
+    audio_source: RustTrackAudioSource,
+    ice_config: IceConfig,
+    resources: DashMap<String, PeerConnection>,
+}
+
+impl WishState {
+    pub(crate) fn new(
+        peer_connection_factory: PeerConnectionFactory,
+        video_source: RustTrackVideoSource,
+        audio_source: RustTrackAudioSource,
+        ice_config: IceConfig,
+    ) -> Self {
+        Self {
+            inner: Arc::new(WishStateInner {
+                peer_connection_factory,
+                video_source,
+                audio_source,
+                ice_config,
+                resources: DashMap::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn resources(&self) -> &DashMap<String, PeerConnection> {
+        &self.inner.resources
+    }
+
+    /// Retargets the one `video_source`/encoder every tracked resource's
+    /// `PeerConnection` shares a clone of. There's no per-connection encoder
+    /// to retarget independently (see `crate::bitrate::spawn_bitrate_sampler`),
+    /// so this is the single knob all resources' bitrate estimates feed into.
+    pub(crate) fn set_target_bitrate(&self, target_bps: u32) {
+        self.inner.video_source.set_bitrate(target_bps);
+    }
+
+    /// A single aggregated `StatsReport` across every tracked resource,
+    /// built in one pass over the resources; each resource's entry comes
+    /// from its own `get_stats` call and channel.
+    pub(crate) fn stats_report(&self) -> crate::rtc_stats::StatsReport {
+        crate::rtc_stats::StatsReport {
+            peer_connections: self
+                .inner
+                .resources
+                .iter()
+                .map(|resource| resource.value().stats_report())
+                .collect(),
+        }
+    }
+}
+
+/// Serves the WHIP (ingest) and WHEP (egress) HTTP front ends on `addr`,
+/// alongside the background AIMD bitrate sampler for every resource it
+/// creates.
+pub(crate) async fn serve(addr: SocketAddr, state: WishState) -> Result<()> {
+    crate::bitrate::spawn_bitrate_sampler(
+        state.clone(),
+        crate::bitrate::BitrateConfig::default(),
+        std::time::Duration::from_secs(2),
+    );
+
+    let app = router(state);
+
+    info!("Starting WHIP/WHEP server on {}", addr);
+
+    Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .map_err(|e| ServerError::WishError(e.to_string()))
+}
+
+fn router(state: WishState) -> Router {
+    Router::new()
+        .route("/whip", post(create_resource))
+        .route("/whep", post(create_resource))
+        .route("/resource/:resource_id", delete(delete_resource))
+        .route("/resource/:resource_id", patch(patch_resource))
+        .route("/stats", get(get_stats))
+        .with_state(state)
+}
+
+/// `GET /stats`: an aggregated `StatsReport` across every resource this
+/// server is currently tracking -- the primary observability surface at
+/// load-test scale.
+async fn get_stats(State(state): State<WishState>) -> Json<crate::rtc_stats::StatsReport> {
+    Json(state.stats_report())
+}
+
+/// `POST /whip` or `POST /whep`: accepts an SDP offer, builds a
+/// `PeerConnection`, and answers with the SDP + a `Location` resource URL.
+async fn create_resource(
+    State(state): State<WishState>,
+    offer_sdp: String,
+) -> std::result::Result<Response, ServerError> {
+    let resource_id = nanoid!();
+
+    let mut peer_connection = PeerConnection::new(
+        &state.inner.peer_connection_factory,
+        &state.inner.video_source,
+        &state.inner.audio_source,
+        &state.inner.ice_config,
+        resource_id.clone(),
+        "wish".into(),
+    )?;
+
+    peer_connection.set_remote_description(SdpType::Offer, offer_sdp)?;
+    let answer = peer_connection.create_answer()?;
+    peer_connection.set_local_description(SdpType::Answer, answer.to_string())?;
+
+    state.inner.resources.insert(resource_id.clone(), peer_connection);
+
+    info!("Created WISH resource {}", resource_id);
+
+    let mut response = (StatusCode::CREATED, answer.to_string()).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::LOCATION,
+        HeaderValue::from_str(&format!("/resource/{}", resource_id))
+            .map_err(|e| ServerError::WishError(e.to_string()))?,
+    );
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(SDP_CONTENT_TYPE));
+
+    Ok(response)
+}
+
+/// `DELETE /resource/{resource_id}`: tears down the peer connection created
+/// by the matching `POST`.
+async fn delete_resource(
+    State(state): State<WishState>,
+    Path(resource_id): Path<String>,
+) -> std::result::Result<StatusCode, ServerError> {
+    state
+        .inner
+        .resources
+        .remove(&resource_id)
+        .ok_or_else(|| ServerError::InvalidPeerConnection(format!("resource {} not found", resource_id)))?;
+
+    info!("Deleted WISH resource {}", resource_id);
+
+    Ok(StatusCode::OK)
+}
+
+/// `PATCH /resource/{resource_id}`: reserved for trickle-ICE candidate
+/// updates; not yet implemented here.
+async fn patch_resource(
+    State(_state): State<WishState>,
+    Path(_resource_id): Path<String>,
+) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peer_connection::tests::peer_connection_params;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn test_state() -> WishState {
+        let (peer_connection_factory, video_source, audio_source) = peer_connection_params();
+        WishState::new(peer_connection_factory, video_source, audio_source, IceConfig::default())
+    }
+
+    /// Builds a standalone offer SDP (from a throwaway `PeerConnection`, not
+    /// tracked by `state`) to post to `/whip`, mirroring a real WHIP client.
+    fn offer_sdp() -> String {
+        let (factory, video_source, audio_source) = peer_connection_params();
+        let mut offerer =
+            PeerConnection::new(&factory, &video_source, &audio_source, &IceConfig::default(), nanoid!(), "offerer".into())
+                .unwrap();
+
+        offerer.create_offer().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn it_creates_and_deletes_a_whip_resource() {
+        let state = test_state();
+        let app = router(state.clone());
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/whip")
+                    .body(Body::from(offer_sdp()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(StatusCode::CREATED, response.status());
+        assert_eq!(1, state.resources().len());
+
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri(&location)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(0, state.resources().len());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_deleting_an_unknown_resource() {
+        let app = router(test_state());
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("DELETE")
+                    .uri("/resource/does-not-exist")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+    }
+}